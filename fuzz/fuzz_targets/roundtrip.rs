@@ -0,0 +1,29 @@
+/*
+ * Copyright (c) 2018 Pascal Bach
+ * Copyright (c) 2021 Siemens Mobility GmbH
+ *
+ * SPDX-License-Identifier:     MIT
+ */
+
+#![no_main]
+
+use junit_report::{Report, ReportOptions};
+use libfuzzer_sys::fuzz_target;
+
+// Every report that can be parsed must be written without error and
+// read back unchanged, unless the writer had to adjust its content.
+fuzz_target!(|data: &[u8]| {
+    let Ok(report) = Report::from_reader(data) else {
+        return;
+    };
+
+    let mut xml = Vec::new();
+    let warnings = report
+        .write_xml_with_warnings(&mut xml, &ReportOptions::default())
+        .expect("parsed report can be written");
+
+    let reparsed = Report::from_reader(xml.as_slice()).expect("written report can be parsed");
+    if warnings.is_empty() {
+        assert_eq!(report, reparsed);
+    }
+});
@@ -0,0 +1,53 @@
+/*
+ * Copyright (c) 2018 Pascal Bach
+ * Copyright (c) 2021 Siemens Mobility GmbH
+ *
+ * SPDX-License-Identifier:     MIT
+ */
+
+//! Conversion of [`chrono`](https://docs.rs/chrono) timestamps.
+
+use ::chrono::{DateTime, Utc};
+use time::{macros::datetime, OffsetDateTime};
+
+use crate::{TestSuite, TestSuiteBuilder};
+
+/// First moment that can be written as RFC 3339 timestamp.
+const FIRST: OffsetDateTime = datetime!(0000-01-01 0:00 UTC);
+/// Last moment that can be written as RFC 3339 timestamp.
+const LAST: OffsetDateTime = datetime!(9999-12-31 23:59:59.999_999_999 UTC);
+
+/// Convert a `chrono` timestamp into an [`OffsetDateTime`] in UTC.
+///
+/// Leap seconds, which `chrono` represents as a nanosecond value of one second or
+/// more, are clamped to the end of the previous second. Timestamps outside of the
+/// years 0000 to 9999, which RFC 3339 cannot represent, are clamped to the first
+/// or last moment of this range.
+fn to_offset_date_time(timestamp: DateTime<Utc>) -> OffsetDateTime {
+    let nanos = timestamp.timestamp_subsec_nanos().min(999_999_999);
+    OffsetDateTime::from_unix_timestamp(timestamp.timestamp())
+        .and_then(|t| t.replace_nanosecond(nanos))
+        .unwrap_or(if timestamp.timestamp() < 0 {
+            FIRST
+        } else {
+            LAST
+        })
+        .clamp(FIRST, LAST)
+}
+
+impl TestSuite {
+    /// Set the timestamp of the `TestSuite` from a `chrono` timestamp,
+    /// see [`set_timestamp`](#method.set_timestamp).
+    pub fn set_timestamp_chrono(&mut self, timestamp: DateTime<Utc>) {
+        self.set_timestamp(to_offset_date_time(timestamp));
+    }
+}
+
+impl TestSuiteBuilder {
+    /// Set the timestamp of the `TestSuiteBuilder` from a `chrono` timestamp,
+    /// see [`TestSuite::set_timestamp_chrono`].
+    pub fn set_timestamp_chrono(&mut self, timestamp: DateTime<Utc>) -> &mut Self {
+        self.testsuite.set_timestamp_chrono(timestamp);
+        self
+    }
+}
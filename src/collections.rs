@@ -5,32 +5,102 @@
  * SPDX-License-Identifier:     MIT
  */
 
-use derive_getters::Getters;
+use std::any::Any;
+use std::collections::BTreeMap;
+use std::fmt;
+
 use time::{Duration, OffsetDateTime};
 
 /// A `TestSuite` groups together several [`TestCase`s](struct.TestCase.html).
-#[derive(Debug, Clone, Getters)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct TestSuite {
     pub name: String,
     pub package: String,
-    pub timestamp: OffsetDateTime,
+    /// Start time of the test suite, the `timestamp` attribute is omitted if `None`
+    pub timestamp: Option<OffsetDateTime>,
     pub hostname: String,
+    /// Source file that produced the test suite, written as `file` attribute
+    pub filepath: Option<String>,
+    pub properties: Vec<Property>,
     pub testcases: Vec<TestCase>,
-    pub system_out: Option<String>,
-    pub system_err: Option<String>,
+    pub system_out: Vec<String>,
+    pub system_err: Vec<String>,
+    /// Explicit suite time replacing the sum of the test case times, see [`set_time`](#method.set_time)
+    pub time_override: Option<Duration>,
+    /// Time added on top of the suite time, see [`add_time`](#method.add_time)
+    pub extra_time: Duration,
 }
 
 impl TestSuite {
+    /// Name of the test suite
+    pub fn name(&self) -> &String {
+        &self.name
+    }
+
+    /// Package of the test suite, written by the Ant dialect
+    pub fn package(&self) -> &String {
+        &self.package
+    }
+
+    /// Start time of the test suite, if set
+    pub fn timestamp(&self) -> &Option<OffsetDateTime> {
+        &self.timestamp
+    }
+
+    /// Host the test suite ran on
+    pub fn hostname(&self) -> &String {
+        &self.hostname
+    }
+
+    /// Source file that produced the test suite
+    pub fn filepath(&self) -> &Option<String> {
+        &self.filepath
+    }
+
+    /// Properties of the test suite
+    pub fn properties(&self) -> &Vec<Property> {
+        &self.properties
+    }
+
+    /// Test cases of the test suite
+    pub fn testcases(&self) -> &Vec<TestCase> {
+        &self.testcases
+    }
+
+    /// Content of the `<system-out>` elements of the test suite
+    pub fn system_out(&self) -> &Vec<String> {
+        &self.system_out
+    }
+
+    /// Content of the `<system-err>` elements of the test suite
+    pub fn system_err(&self) -> &Vec<String> {
+        &self.system_err
+    }
+
+    /// Explicit suite time replacing the sum of the test case times, see [`set_time`](#method.set_time)
+    pub fn time_override(&self) -> &Option<Duration> {
+        &self.time_override
+    }
+
+    /// Time added on top of the suite time, see [`add_time`](#method.add_time)
+    pub fn extra_time(&self) -> &Duration {
+        &self.extra_time
+    }
+
     /// Create a new `TestSuite` with a given name
     pub fn new(name: &str) -> Self {
         TestSuite {
             hostname: "localhost".into(),
             package: format!("testsuite/{}", &name),
             name: name.into(),
-            timestamp: OffsetDateTime::now_utc(),
+            timestamp: Some(OffsetDateTime::now_utc()),
+            filepath: None,
+            properties: Vec::new(),
             testcases: Vec::new(),
-            system_out: None,
-            system_err: None,
+            system_out: Vec::new(),
+            system_err: Vec::new(),
+            time_override: None,
+            extra_time: Duration::ZERO,
         }
     }
 
@@ -44,51 +114,316 @@ impl TestSuite {
         self.testcases.extend(testcases);
     }
 
+    /// Find the [`TestCase`](struct.TestCase.html) with the given `name`.
+    ///
+    /// If several test cases share the name, the first one is returned.
+    #[must_use]
+    pub fn testcase_by_name(&self, name: &str) -> Option<&TestCase> {
+        self.testcases.iter().find(|tc| tc.name == name)
+    }
+
+    /// Find the [`TestCase`](struct.TestCase.html) with the given `name` to modify it.
+    ///
+    /// If several test cases share the name, the first one is returned.
+    pub fn testcase_by_name_mut(&mut self, name: &str) -> Option<&mut TestCase> {
+        self.testcases.iter_mut().find(|tc| tc.name == name)
+    }
+
+    /// Remove all test cases, properties, output and explicit times from the `TestSuite`.
+    ///
+    /// The `name`, `package`, `hostname`, `file` and `timestamp` are retained, which allows
+    /// to reuse the `TestSuite` for another run without reallocating.
+    pub fn clear(&mut self) {
+        self.properties.clear();
+        self.testcases.clear();
+        self.system_out.clear();
+        self.system_err.clear();
+        self.time_override = None;
+        self.extra_time = Duration::ZERO;
+    }
+
+    /// Set the time of the `TestSuite` explicitly.
+    ///
+    /// The given time replaces the sum of the test case times and discards any
+    /// time added with [`add_time`](#method.add_time) before.
+    pub fn set_time(&mut self, time: Duration) {
+        self.time_override = Some(time);
+        self.extra_time = Duration::ZERO;
+    }
+
+    /// Add `extra` time to the `TestSuite`, e.g. for fixture setup not attributed to any test case.
+    ///
+    /// The extra time is added on top of the time set with [`set_time`](#method.set_time)
+    /// or, if no time was set, on top of the sum of the test case times.
+    pub fn add_time(&mut self, extra: Duration) {
+        self.extra_time = self.extra_time.saturating_add(extra);
+    }
+
     /// Set the timestamp of the given `TestSuite`.
     ///
     /// By default the timestamp is set to the time when the `TestSuite` was created.
     pub fn set_timestamp(&mut self, timestamp: OffsetDateTime) {
-        self.timestamp = timestamp;
+        self.timestamp = Some(timestamp);
+    }
+
+    /// Remove the timestamp of the `TestSuite`, the `timestamp` attribute is then omitted.
+    ///
+    /// This makes the written report independent of the time it was created at.
+    pub fn clear_timestamp(&mut self) {
+        self.timestamp = None;
     }
 
+    /// Set the name of the `TestSuite`.
+    ///
+    /// The package is derived from the name only when the `TestSuite` is created. With
+    /// `derive_package` it is reset to `testsuite/{name}` for the new name, replacing any
+    /// package set before, otherwise it is left unchanged.
+    pub fn set_name(&mut self, name: &str, derive_package: bool) {
+        self.name = name.to_owned();
+        if derive_package {
+            self.package = format!("testsuite/{}", name);
+        }
+    }
+
+    /// Set the package of the `TestSuite`, which defaults to `testsuite/{name}`.
+    ///
+    /// The package is kept when the name is changed with `set_name` without `derive_package`.
+    pub fn set_package(&mut self, package: &str) {
+        self.package = package.to_owned();
+    }
+
+    /// Set the `file` of the `TestSuite`, the source file that produced it
+    pub fn set_filepath(&mut self, filepath: &str) {
+        self.filepath = Some(filepath.to_owned());
+    }
+
+    /// Add a [`Property`](struct.Property.html) to the `TestSuite`.
+    ///
+    /// Properties are written in the order they were added.
+    pub fn add_property(&mut self, name: &str, value: &str) {
+        self.properties.push(Property::new(name, value));
+    }
+
+    /// Set the `system_out` of the `TestSuite`, replacing all previous `<system-out>` elements
     pub fn set_system_out(&mut self, system_out: &str) {
-        self.system_out = Some(system_out.to_owned());
+        self.system_out = vec![system_out.to_owned()];
     }
 
+    /// Set the `system_err` of the `TestSuite`, replacing all previous `<system-err>` elements
     pub fn set_system_err(&mut self, system_err: &str) {
-        self.system_err = Some(system_err.to_owned());
+        self.system_err = vec![system_err.to_owned()];
+    }
+
+    /// Add another `<system-out>` element to the `TestSuite`
+    pub fn add_system_out(&mut self, system_out: &str) {
+        self.system_out.push(system_out.to_owned());
     }
 
+    /// Add another `<system-err>` element to the `TestSuite`
+    pub fn add_system_err(&mut self, system_err: &str) {
+        self.system_err.push(system_err.to_owned());
+    }
+
+    /// Number of test cases in the `TestSuite`.
+    ///
+    /// Every test case has exactly one result, so `tests` always equals the number of
+    /// successful test cases plus [`errors`](#method.errors), [`failures`](#method.failures),
+    /// [`skipped`](#method.skipped) and [`disabled`](#method.disabled).
+    #[must_use]
     pub fn tests(&self) -> usize {
         self.testcases.len()
     }
 
+    #[must_use]
     pub fn errors(&self) -> usize {
         self.testcases.iter().filter(|x| x.is_error()).count()
     }
 
+    #[must_use]
     pub fn failures(&self) -> usize {
         self.testcases.iter().filter(|x| x.is_failure()).count()
     }
 
+    #[must_use]
     pub fn skipped(&self) -> usize {
         self.testcases.iter().filter(|x| x.is_skipped()).count()
     }
 
-    pub fn time(&self) -> Duration {
+    /// Number of disabled test cases, they are not included in [`skipped`](#method.skipped)
+    #[must_use]
+    pub fn disabled(&self) -> usize {
+        self.testcases.iter().filter(|x| x.is_disabled()).count()
+    }
+
+    /// Check if any [`TestCase`](struct.TestCase.html) of the `TestSuite` is erroneous or failed
+    #[must_use]
+    pub fn has_problems(&self) -> bool {
+        self.testcases.iter().any(TestCase::is_problem)
+    }
+
+    /// Same as [`tests`](#method.tests) but as a platform independent `u64`
+    #[must_use]
+    pub fn tests_u64(&self) -> u64 {
+        self.tests() as u64
+    }
+
+    /// Same as [`errors`](#method.errors) but as a platform independent `u64`
+    #[must_use]
+    pub fn errors_u64(&self) -> u64 {
+        self.errors() as u64
+    }
+
+    /// Same as [`failures`](#method.failures) but as a platform independent `u64`
+    #[must_use]
+    pub fn failures_u64(&self) -> u64 {
+        self.failures() as u64
+    }
+
+    /// Same as [`skipped`](#method.skipped) but as a platform independent `u64`
+    #[must_use]
+    pub fn skipped_u64(&self) -> u64 {
+        self.skipped() as u64
+    }
+
+    /// Total number of assertions of all test cases, `None` if no test case has
+    /// its number of assertions set. The sum saturates at `u64::MAX`.
+    #[must_use]
+    pub fn assertions(&self) -> Option<u64> {
         self.testcases
             .iter()
-            .fold(Duration::ZERO, |sum, d| sum + d.time)
+            .filter_map(|tc| tc.assertions)
+            .reduce(|sum, assertions| sum.saturating_add(assertions))
+    }
+
+    /// Same as [`disabled`](#method.disabled) but as a platform independent `u64`
+    #[must_use]
+    pub fn disabled_u64(&self) -> u64 {
+        self.disabled() as u64
+    }
+
+    /// Time of the `TestSuite`.
+    ///
+    /// This is the time set with [`set_time`](#method.set_time) or, if no time was set,
+    /// the sum of the test case times. Time added with [`add_time`](#method.add_time)
+    /// is included in both cases. The sum saturates at the limits of [`Duration`].
+    #[must_use]
+    pub fn time(&self) -> Duration {
+        let time = self.time_override.unwrap_or_else(|| {
+            self.testcases
+                .iter()
+                .fold(Duration::ZERO, |sum, d| sum.saturating_add(d.time))
+        });
+        time.saturating_add(self.extra_time)
+    }
+
+    /// Compute the [`Summary`](struct.Summary.html) of the `TestSuite` in a single pass.
+    ///
+    /// The `time` of the summary follows the same rules as [`time`](#method.time).
+    #[must_use]
+    pub fn summary(&self) -> Summary {
+        let mut summary = self
+            .testcases
+            .iter()
+            .fold(Summary::default(), |mut summary, tc| {
+                summary.tests += 1;
+                match tc.status() {
+                    Status::Passed => {}
+                    Status::Errored => summary.errors += 1,
+                    Status::Failed => summary.failures += 1,
+                    Status::Skipped => summary.skipped += 1,
+                    Status::Disabled => summary.disabled += 1,
+                }
+                summary.assertions = add_assertions(summary.assertions, tc.assertions);
+                summary.time = summary.time.saturating_add(tc.time);
+                summary
+            });
+        if let Some(time) = self.time_override {
+            summary.time = time;
+        }
+        summary.time = summary.time.saturating_add(self.extra_time);
+        summary
+    }
+}
+
+/// Aggregated counts and time of a [`TestSuite`](struct.TestSuite.html) or a
+/// whole [`Report`](struct.Report.html).
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Summary {
+    pub tests: usize,
+    pub errors: usize,
+    pub failures: usize,
+    pub skipped: usize,
+    pub disabled: usize,
+    /// Time of the test suite as returned by
+    /// [`TestSuite::time`](struct.TestSuite.html#method.time), for a report the
+    /// saturating sum of the times of its test suites
+    pub time: Duration,
+    /// Total number of assertions, `None` if no test case has its number of assertions set
+    pub assertions: Option<u64>,
+}
+
+impl Summary {
+    /// Number of test cases
+    pub fn tests(&self) -> &usize {
+        &self.tests
+    }
+
+    /// Number of erroneous test cases
+    pub fn errors(&self) -> &usize {
+        &self.errors
+    }
+
+    /// Number of failed test cases
+    pub fn failures(&self) -> &usize {
+        &self.failures
+    }
+
+    /// Number of skipped test cases
+    pub fn skipped(&self) -> &usize {
+        &self.skipped
+    }
+
+    /// Number of disabled test cases
+    pub fn disabled(&self) -> &usize {
+        &self.disabled
+    }
+
+    /// Time of the test suite as returned by
+    /// [`TestSuite::time`](struct.TestSuite.html#method.time), for a report the
+    /// saturating sum of the times of its test suites
+    pub fn time(&self) -> &Duration {
+        &self.time
+    }
+
+    /// Total number of assertions, `None` if no test case has its number of assertions set
+    pub fn assertions(&self) -> &Option<u64> {
+        &self.assertions
+    }
+}
+
+/// Add two optional numbers of assertions, `None` only if both are `None`.
+///
+/// The sum saturates at `u64::MAX`.
+pub(crate) fn add_assertions(a: Option<u64>, b: Option<u64>) -> Option<u64> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.saturating_add(b)),
+        (a, b) => a.or(b),
     }
 }
 
 ///  Builder for [`TestSuite`](struct.TestSuite.html) objects.
-#[derive(Debug, Clone, Getters)]
+#[derive(Debug, Clone)]
 pub struct TestSuiteBuilder {
     pub testsuite: TestSuite,
 }
 
 impl TestSuiteBuilder {
+    /// The `TestSuite` being built
+    pub fn testsuite(&self) -> &TestSuite {
+        &self.testsuite
+    }
+
     /// Create a new `TestSuiteBuilder` with a given name
     pub fn new(name: &str) -> Self {
         TestSuiteBuilder {
@@ -96,6 +431,11 @@ impl TestSuiteBuilder {
         }
     }
 
+    /// Create a new `TestSuiteBuilder` to edit an existing [`TestSuite`](struct.TestSuite.html)
+    pub fn from_testsuite(testsuite: TestSuite) -> Self {
+        TestSuiteBuilder { testsuite }
+    }
+
     /// Add a [`TestCase`](struct.TestCase.html) to the `TestSuiteBuilder`.
     pub fn add_testcase(&mut self, testcase: TestCase) -> &mut Self {
         self.testsuite.testcases.push(testcase);
@@ -112,55 +452,359 @@ impl TestSuiteBuilder {
     ///
     /// By default the timestamp is set to the time when the `TestSuiteBuilder` was created.
     pub fn set_timestamp(&mut self, timestamp: OffsetDateTime) -> &mut Self {
-        self.testsuite.timestamp = timestamp;
+        self.testsuite.set_timestamp(timestamp);
+        self
+    }
+
+    /// Remove the timestamp of the `TestSuiteBuilder`, see [`TestSuite::clear_timestamp`].
+    pub fn without_timestamp(&mut self) -> &mut Self {
+        self.testsuite.clear_timestamp();
+        self
+    }
+
+    /// Set the name of the `TestSuiteBuilder`, see [`TestSuite::set_name`].
+    pub fn set_name(&mut self, name: &str, derive_package: bool) -> &mut Self {
+        self.testsuite.set_name(name, derive_package);
+        self
+    }
+
+    /// Set the package of the `TestSuiteBuilder`, which defaults to `testsuite/{name}`.
+    pub fn set_package(&mut self, package: &str) -> &mut Self {
+        self.testsuite.set_package(package);
+        self
+    }
+
+    /// Set the `file` of the `TestSuiteBuilder`, see [`TestSuite::set_filepath`]
+    pub fn set_filepath(&mut self, filepath: &str) -> &mut Self {
+        self.testsuite.set_filepath(filepath);
+        self
+    }
+
+    /// Add a [`Property`](struct.Property.html) to the `TestSuiteBuilder`.
+    ///
+    /// Properties are written in the order they were added.
+    pub fn add_property(&mut self, name: &str, value: &str) -> &mut Self {
+        self.testsuite.add_property(name, value);
         self
     }
 
     pub fn set_system_out(&mut self, system_out: &str) -> &mut Self {
-        self.testsuite.system_out = Some(system_out.to_owned());
+        self.testsuite.system_out = vec![system_out.to_owned()];
         self
     }
 
     pub fn set_system_err(&mut self, system_err: &str) -> &mut Self {
-        self.testsuite.system_err = Some(system_err.to_owned());
+        self.testsuite.system_err = vec![system_err.to_owned()];
+        self
+    }
+
+    /// Add another `<system-out>` element to the `TestSuiteBuilder`
+    pub fn add_system_out(&mut self, system_out: &str) -> &mut Self {
+        self.testsuite.add_system_out(system_out);
+        self
+    }
+
+    /// Add another `<system-err>` element to the `TestSuiteBuilder`
+    pub fn add_system_err(&mut self, system_err: &str) -> &mut Self {
+        self.testsuite.add_system_err(system_err);
+        self
+    }
+
+    /// Set the time of the `TestSuiteBuilder` explicitly, see [`TestSuite::set_time`].
+    pub fn set_time(&mut self, time: Duration) -> &mut Self {
+        self.testsuite.set_time(time);
+        self
+    }
+
+    /// Add `extra` time to the `TestSuiteBuilder`, see [`TestSuite::add_time`].
+    pub fn add_time(&mut self, extra: Duration) -> &mut Self {
+        self.testsuite.add_time(extra);
         self
     }
 
     /// Build and return a [`TestSuite`](struct.TestSuite.html) object based on the data stored in this TestSuiteBuilder object.
+    #[must_use]
     pub fn build(&self) -> TestSuite {
         self.testsuite.clone()
     }
 }
 
+/// A named value attached to a [`TestSuite`](struct.TestSuite.html) or [`TestCase`](struct.TestCase.html)
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Property {
+    pub name: String,
+    pub value: String,
+}
+
+impl Property {
+    /// Name of the property
+    pub fn name(&self) -> &String {
+        &self.name
+    }
+
+    /// Value of the property
+    pub fn value(&self) -> &String {
+        &self.value
+    }
+
+    /// Create a new `Property` with the given name and value
+    pub fn new(name: &str, value: &str) -> Self {
+        Property {
+            name: name.into(),
+            value: value.into(),
+        }
+    }
+}
+
+impl From<TestSuite> for TestSuiteBuilder {
+    fn from(testsuite: TestSuite) -> Self {
+        TestSuiteBuilder::from_testsuite(testsuite)
+    }
+}
+
 /// One single test case
-#[derive(Debug, Clone, Getters)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TestCase {
     pub name: String,
     pub time: Duration,
     pub result: TestResult,
     pub classname: Option<String>,
     pub filepath: Option<String>,
-    pub system_out: Option<String>,
-    pub system_err: Option<String>,
+    /// Line in `filepath` where the test case is defined, if known.
+    pub line: Option<u64>,
+    pub system_out: Vec<String>,
+    pub system_err: Vec<String>,
+    /// Number of assertions executed by the test case, if known.
+    pub assertions: Option<u64>,
+    /// Additional key/value pairs for the `serde` output, they are not written to XML.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "BTreeMap::is_empty")
+    )]
+    pub metadata: BTreeMap<String, String>,
+    /// Host the test case ran on, if it differs from the `hostname` of its suite.
+    pub hostname: Option<String>,
+    /// Group or tag of the test case, e.g. `smoke` or `regression`.
+    pub group: Option<String>,
+    /// Time the test case started, if known.
+    pub timestamp: Option<OffsetDateTime>,
+    /// Properties of the test case, e.g. the parameters of a data-driven test.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub properties: Vec<Property>,
+    /// Further `<error>` and `<failure>` elements written after `result`, see
+    /// [`add_failure`](#method.add_failure) and [`add_error`](#method.add_error).
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub additional_results: Vec<TestResult>,
+}
+
+impl TestCase {
+    /// Name of the test case
+    pub fn name(&self) -> &String {
+        &self.name
+    }
+
+    /// Time the test case took
+    pub fn time(&self) -> &Duration {
+        &self.time
+    }
+
+    /// Result of the test case
+    pub fn result(&self) -> &TestResult {
+        &self.result
+    }
+
+    /// Class name of the test case, if set
+    pub fn classname(&self) -> &Option<String> {
+        &self.classname
+    }
+
+    /// File of the test case, if set
+    pub fn filepath(&self) -> &Option<String> {
+        &self.filepath
+    }
+
+    /// Line in `filepath` where the test case is defined, if known.
+    pub fn line(&self) -> &Option<u64> {
+        &self.line
+    }
+
+    /// Content of the `<system-out>` elements of the test case
+    pub fn system_out(&self) -> &Vec<String> {
+        &self.system_out
+    }
+
+    /// Content of the `<system-err>` elements of the test case
+    pub fn system_err(&self) -> &Vec<String> {
+        &self.system_err
+    }
+
+    /// Number of assertions executed by the test case, if known.
+    pub fn assertions(&self) -> &Option<u64> {
+        &self.assertions
+    }
+
+    /// Additional key/value pairs for the `serde` output, they are not written to XML.
+    pub fn metadata(&self) -> &BTreeMap<String, String> {
+        &self.metadata
+    }
+
+    /// Host the test case ran on, if it differs from the `hostname` of its suite.
+    pub fn hostname(&self) -> &Option<String> {
+        &self.hostname
+    }
+
+    /// Group or tag of the test case, e.g. `smoke` or `regression`.
+    pub fn group(&self) -> &Option<String> {
+        &self.group
+    }
+
+    /// Time the test case started, if known.
+    pub fn timestamp(&self) -> &Option<OffsetDateTime> {
+        &self.timestamp
+    }
+
+    /// Properties of the test case, e.g. the parameters of a data-driven test.
+    pub fn properties(&self) -> &Vec<Property> {
+        &self.properties
+    }
+
+    /// Further `<error>` and `<failure>` elements written after `result`, see
+    /// [`add_failure`](#method.add_failure) and [`add_error`](#method.add_error).
+    pub fn additional_results(&self) -> &Vec<TestResult> {
+        &self.additional_results
+    }
 }
 
 /// Result of a test case
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum TestResult {
     Success,
-    Skipped,
+    /// The test case was skipped at runtime, e.g. because a precondition was not met.
+    ///
+    /// The optional `message` gives the reason and is written as `message` attribute.
+    Skipped {
+        message: Option<String>,
+    },
+    /// The test case was explicitly turned off and not run at all, e.g. with `#[ignore]`.
+    ///
+    /// It is written as `<skipped type="disabled"/>` and counted in the `disabled`
+    /// attribute of the test suite instead of `skipped`.
+    Disabled,
     Error {
         type_: String,
         message: String,
         cause: Option<String>,
+        /// File where the error occurred, written as `file` attribute (extension).
+        file: Option<String>,
+        /// Line where the error occurred, written as `line` attribute (extension).
+        line: Option<u64>,
     },
     Failure {
         type_: String,
         message: String,
         cause: Option<String>,
+        /// File where the failure occurred, written as `file` attribute (extension).
+        file: Option<String>,
+        /// Line where the failure occurred, written as `line` attribute (extension).
+        line: Option<u64>,
     },
 }
 
+impl TestResult {
+    /// Creates a [`TestResult::Error`] from an error value
+    ///
+    /// The `type` is the type name of the error and the `message` its
+    /// [`Display`](std::fmt::Display) representation. The chain of
+    /// [`source`](std::error::Error::source) errors, if any, is the `cause` with one
+    /// error per line.
+    pub fn from_error<E: std::error::Error + ?Sized>(e: &E) -> TestResult {
+        let sources: Vec<String> = std::iter::successors(e.source(), |e| e.source())
+            .map(ToString::to_string)
+            .collect();
+        TestResult::Error {
+            type_: std::any::type_name::<E>().into(),
+            message: e.to_string(),
+            cause: Some(sources.join("\n")).filter(|c| !c.is_empty()),
+            file: None,
+            line: None,
+        }
+    }
+
+    /// Check if the result is a problem, i.e. an error or a failure
+    #[must_use]
+    pub fn is_problem(&self) -> bool {
+        matches!(self.status(), Status::Errored | Status::Failed)
+    }
+
+    /// The [`Status`] of the result
+    #[must_use]
+    pub fn status(&self) -> Status {
+        match self {
+            TestResult::Success => Status::Passed,
+            TestResult::Skipped { .. } => Status::Skipped,
+            TestResult::Disabled => Status::Disabled,
+            TestResult::Error { .. } => Status::Errored,
+            TestResult::Failure { .. } => Status::Failed,
+        }
+    }
+}
+
+/// Outcome of a [`TestCase`], the kind of its [`TestResult`] without any details
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum Status {
+    Passed,
+    Failed,
+    Errored,
+    Skipped,
+    Disabled,
+}
+
+/// Renders the result on a single line, e.g. `failure: assert_eq: not equal`.
+///
+/// The cause is not included and line breaks in the message are replaced by spaces.
+impl fmt::Display for TestResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let message = match self {
+            TestResult::Success => return f.write_str("success"),
+            TestResult::Skipped { message: None } => return f.write_str("skipped"),
+            TestResult::Skipped {
+                message: Some(message),
+            } => {
+                f.write_str("skipped: ")?;
+                message
+            }
+            TestResult::Disabled => return f.write_str("disabled"),
+            TestResult::Error { type_, message, .. } => {
+                write!(f, "error: {}: ", type_)?;
+                message
+            }
+            TestResult::Failure { type_, message, .. } => {
+                write!(f, "failure: {}: ", type_)?;
+                message
+            }
+        };
+        let mut first = true;
+        for line in message.lines() {
+            if !first {
+                f.write_str(" ")?;
+            }
+            f.write_str(line)?;
+            first = false;
+        }
+        Ok(())
+    }
+}
+
 impl TestCase {
     /// Creates a new successful `TestCase`
     pub fn success(name: &str, time: Duration) -> Self {
@@ -170,8 +814,26 @@ impl TestCase {
             result: TestResult::Success,
             classname: None,
             filepath: None,
-            system_out: None,
-            system_err: None,
+            line: None,
+            system_out: Vec::new(),
+            system_err: Vec::new(),
+            assertions: None,
+            metadata: BTreeMap::new(),
+            hostname: None,
+            group: None,
+            timestamp: None,
+            properties: Vec::new(),
+            additional_results: Vec::new(),
+        }
+    }
+
+    /// Creates a new successful `TestCase` that ran from `start` to `end`
+    ///
+    /// The `timestamp` is set to `start`. If `end` is before `start` the time is zero.
+    pub fn success_between(name: &str, start: OffsetDateTime, end: OffsetDateTime) -> Self {
+        TestCase {
+            timestamp: Some(start),
+            ..TestCase::success(name, (end - start).max(Duration::ZERO))
         }
     }
 
@@ -185,19 +847,201 @@ impl TestCase {
         self.filepath = Some(filepath.to_owned());
     }
 
-    /// Set the `system_out` for the `TestCase`
+    /// Set the `line` in `file` where the `TestCase` is defined
+    pub fn set_line(&mut self, line: u64) {
+        self.line = Some(line);
+    }
+
+    /// Set the `hostname` of the `TestCase`, e.g. for distributed runs where it
+    /// differs from the `hostname` of the [`TestSuite`]
+    pub fn set_hostname(&mut self, hostname: &str) {
+        self.hostname = Some(hostname.to_owned());
+    }
+
+    /// Set the `group` of the `TestCase`, e.g. a tag like `smoke`
+    pub fn set_group(&mut self, group: &str) {
+        self.group = Some(group.to_owned());
+    }
+
+    /// Add a [`Property`](struct.Property.html) to the `TestCase`
+    ///
+    /// Properties are written as `<properties>` child of the `<testcase>` element.
+    pub fn add_property(&mut self, name: &str, value: &str) {
+        self.properties.push(Property::new(name, value));
+    }
+
+    /// Add a failure to the `TestCase`, e.g. for every failed attempt of a flaky test
+    ///
+    /// The first error or failure becomes the `result` of the `TestCase`, further ones
+    /// are kept in [`additional_results`](#structfield.additional_results) and written
+    /// as additional `<failure>` elements. The `TestCase` is still counted once.
+    pub fn add_failure(&mut self, type_: &str, message: &str) {
+        self.add_problem(TestResult::Failure {
+            type_: type_.into(),
+            message: message.into(),
+            cause: None,
+            file: None,
+            line: None,
+        });
+    }
+
+    /// Add an error to the `TestCase`, see [`add_failure`](#method.add_failure)
+    ///
+    /// The JUnit schema expects all `<error>` elements before the `<failure>` elements,
+    /// so errors should not be added after failures.
+    pub fn add_error(&mut self, type_: &str, message: &str) {
+        self.add_problem(TestResult::Error {
+            type_: type_.into(),
+            message: message.into(),
+            cause: None,
+            file: None,
+            line: None,
+        });
+    }
+
+    /// Set `result` as result if there is no error or failure yet, add it otherwise.
+    pub(crate) fn add_problem(&mut self, result: TestResult) {
+        if self.is_problem() {
+            self.additional_results.push(result);
+        } else {
+            self.result = result;
+        }
+    }
+
+    /// Set the time the `TestCase` started
+    pub fn set_timestamp(&mut self, timestamp: OffsetDateTime) {
+        self.timestamp = Some(timestamp);
+    }
+
+    /// Set the number of assertions executed by the `TestCase`
+    pub fn set_assertions(&mut self, assertions: u64) {
+        self.assertions = Some(assertions);
+    }
+
+    /// Set the `metadata` of the `TestCase`, replacing all previous entries
+    ///
+    /// Metadata is only part of the `serde` output, it is not written to XML.
+    pub fn set_metadata(&mut self, metadata: BTreeMap<String, String>) {
+        self.metadata = metadata;
+    }
+
+    /// Add a `metadata` entry to the `TestCase`, replacing a previous value of `key`
+    ///
+    /// Metadata is only part of the `serde` output, it is not written to XML.
+    pub fn add_metadata(&mut self, key: &str, value: &str) {
+        self.metadata.insert(key.to_owned(), value.to_owned());
+    }
+
+    /// Set the `system_out` for the `TestCase`, replacing all previous `<system-out>` elements
     pub fn set_system_out(&mut self, system_out: &str) {
-        self.system_out = Some(system_out.to_owned());
+        self.system_out = vec![system_out.to_owned()];
     }
 
-    /// Set the `system_err` for the `TestCase`
+    /// Set the `system_err` for the `TestCase`, replacing all previous `<system-err>` elements
     pub fn set_system_err(&mut self, system_err: &str) {
-        self.system_err = Some(system_err.to_owned());
+        self.system_err = vec![system_err.to_owned()];
+    }
+
+    /// Add another `<system-out>` element to the `TestCase`, e.g. for a separate test phase
+    pub fn add_system_out(&mut self, system_out: &str) {
+        self.system_out.push(system_out.to_owned());
+    }
+
+    /// Add another `<system-err>` element to the `TestCase`, e.g. for a separate test phase
+    pub fn add_system_err(&mut self, system_err: &str) {
+        self.system_err.push(system_err.to_owned());
+    }
+
+    /// Set the `classname` for the `TestCase` and return it
+    ///
+    /// ```rust
+    /// use junit_report::{Duration, TestCase};
+    ///
+    /// let tc = TestCase::success("good test", Duration::ZERO)
+    ///     .with_classname("MyClass")
+    ///     .with_filepath("./foo.rs")
+    ///     .with_system_out("sysout")
+    ///     .with_system_err("syserr")
+    ///     .with_time(Duration::seconds(15));
+    ///
+    /// assert_eq!(tc.classname.as_deref(), Some("MyClass"));
+    /// assert_eq!(tc.time, Duration::seconds(15));
+    /// ```
+    pub fn with_classname(mut self, classname: &str) -> Self {
+        self.set_classname(classname);
+        self
+    }
+
+    /// Set the `file` for the `TestCase` and return it
+    pub fn with_filepath(mut self, filepath: &str) -> Self {
+        self.set_filepath(filepath);
+        self
+    }
+
+    /// Set the `line` for the `TestCase` and return it
+    pub fn with_line(mut self, line: u64) -> Self {
+        self.set_line(line);
+        self
+    }
+
+    /// Set the `system_out` for the `TestCase` and return it
+    pub fn with_system_out(mut self, system_out: &str) -> Self {
+        self.set_system_out(system_out);
+        self
+    }
+
+    /// Set the `system_err` for the `TestCase` and return it
+    pub fn with_system_err(mut self, system_err: &str) -> Self {
+        self.set_system_err(system_err);
+        self
+    }
+
+    /// Set the `time` for the `TestCase` and return it
+    ///
+    /// ```rust
+    /// use junit_report::{Duration, TestCase};
+    ///
+    /// let tc = TestCase::failure("failure test", Duration::ZERO, "assert_eq", "not equal")
+    ///     .with_time(Duration::milliseconds(1500));
+    ///
+    /// assert!(tc.is_failure());
+    /// assert_eq!(tc.time, Duration::milliseconds(1500));
+    /// ```
+    pub fn with_time(mut self, time: Duration) -> Self {
+        self.time = time;
+        self
+    }
+
+    /// Append a chunk to the last `system_out` of the `TestCase`
+    pub fn append_system_out(&mut self, chunk: &str) {
+        append(&mut self.system_out, chunk);
+    }
+
+    /// Append a chunk to the last `system_err` of the `TestCase`
+    pub fn append_system_err(&mut self, chunk: &str) {
+        append(&mut self.system_err, chunk);
+    }
+
+    /// Sorting key ordering `TestCase`s by duration and then by name
+    ///
+    /// ```rust
+    /// use junit_report::{Duration, TestCase};
+    ///
+    /// let a = TestCase::success("a", Duration::seconds(2));
+    /// let b = TestCase::success("b", Duration::seconds(1));
+    /// let mut cases = vec![&a, &b];
+    /// cases.sort_by_key(|&tc| tc.by_duration());
+    /// assert_eq!(cases[0].name, "b");
+    /// ```
+    #[must_use]
+    pub fn by_duration(&self) -> (Duration, &str) {
+        (self.time, self.name.as_str())
     }
 
     /// Check if a `TestCase` is successful
+    #[must_use]
     pub fn is_success(&self) -> bool {
-        matches!(self.result, TestResult::Success)
+        self.status() == Status::Passed
     }
 
     /// Creates a new erroneous `TestCase`
@@ -211,17 +1055,50 @@ impl TestCase {
                 type_: type_.into(),
                 message: message.into(),
                 cause: None,
+                file: None,
+                line: None,
             },
             classname: None,
             filepath: None,
-            system_out: None,
-            system_err: None,
+            line: None,
+            system_out: Vec::new(),
+            system_err: Vec::new(),
+            assertions: None,
+            metadata: BTreeMap::new(),
+            hostname: None,
+            group: None,
+            timestamp: None,
+            properties: Vec::new(),
+            additional_results: Vec::new(),
+        }
+    }
+
+    /// Creates a new erroneous `TestCase` with the given `body`, e.g. a stack trace
+    ///
+    /// The `body` is written as CDATA inside the `<error>` element.
+    pub fn error_with_body(
+        name: &str,
+        time: Duration,
+        type_: &str,
+        message: &str,
+        body: &str,
+    ) -> Self {
+        TestCase {
+            result: TestResult::Error {
+                type_: type_.into(),
+                message: message.into(),
+                cause: Some(body.into()),
+                file: None,
+                line: None,
+            },
+            ..TestCase::success(name, time)
         }
     }
 
     /// Check if a `TestCase` is erroneous
+    #[must_use]
     pub fn is_error(&self) -> bool {
-        matches!(self.result, TestResult::Error { .. })
+        self.status() == Status::Errored
     }
 
     /// Creates a new failed `TestCase`
@@ -235,17 +1112,137 @@ impl TestCase {
                 type_: type_.into(),
                 message: message.into(),
                 cause: None,
+                file: None,
+                line: None,
             },
             classname: None,
             filepath: None,
-            system_out: None,
-            system_err: None,
+            line: None,
+            system_out: Vec::new(),
+            system_err: Vec::new(),
+            assertions: None,
+            metadata: BTreeMap::new(),
+            hostname: None,
+            group: None,
+            timestamp: None,
+            properties: Vec::new(),
+            additional_results: Vec::new(),
+        }
+    }
+
+    /// Creates a new failed `TestCase` with the given `body`, e.g. a stack trace
+    ///
+    /// The `body` is written as CDATA inside the `<failure>` element.
+    pub fn failure_with_body(
+        name: &str,
+        time: Duration,
+        type_: &str,
+        message: &str,
+        body: &str,
+    ) -> Self {
+        TestCase {
+            result: TestResult::Failure {
+                type_: type_.into(),
+                message: message.into(),
+                cause: Some(body.into()),
+                file: None,
+                line: None,
+            },
+            ..TestCase::success(name, time)
+        }
+    }
+
+    /// Creates a successful `TestCase` for `Ok` or a failed `TestCase` for `Err`
+    ///
+    /// The `type` of the failure is the type name of the error and the `message`
+    /// its [`Display`](std::fmt::Display) representation.
+    pub fn from_result<E: fmt::Display>(name: &str, time: Duration, result: Result<(), E>) -> Self {
+        match result {
+            Ok(()) => TestCase::success(name, time),
+            Err(e) => TestCase::failure(name, time, std::any::type_name::<E>(), &e.to_string()),
+        }
+    }
+
+    /// Creates an erroneous `TestCase` from an error value, see [`TestResult::from_error`]
+    pub fn error_from<E: std::error::Error + ?Sized>(name: &str, time: Duration, e: &E) -> Self {
+        TestCase {
+            result: TestResult::from_error(e),
+            ..TestCase::success(name, time)
         }
     }
 
+    /// Creates a failed `TestCase` from the payload of a caught panic,
+    /// e.g. returned by [`std::panic::catch_unwind`]
+    ///
+    /// The `type` of the failure is `panic` and the `message` the panic message if the
+    /// payload is a string, as for [`panic!`] with a message.
+    pub fn from_panic(name: &str, time: Duration, payload: &(dyn Any + Send)) -> Self {
+        let message = payload
+            .downcast_ref::<&str>()
+            .copied()
+            .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+            .unwrap_or("Box<dyn Any>");
+        TestCase::failure(name, time, "panic", message)
+    }
+
     /// Check if a `TestCase` failed
+    #[must_use]
     pub fn is_failure(&self) -> bool {
-        matches!(self.result, TestResult::Failure { .. })
+        self.status() == Status::Failed
+    }
+
+    /// The `type` of a failed `TestCase`, `None` for other results
+    #[must_use]
+    pub fn failure_type(&self) -> Option<&str> {
+        match &self.result {
+            TestResult::Failure { type_, .. } => Some(type_),
+            _ => None,
+        }
+    }
+
+    /// The `message` of a failed `TestCase`, `None` for other results
+    #[must_use]
+    pub fn failure_message(&self) -> Option<&str> {
+        match &self.result {
+            TestResult::Failure { message, .. } => Some(message),
+            _ => None,
+        }
+    }
+
+    /// The trace of a failed `TestCase`, `None` for other results or if no trace is set
+    #[must_use]
+    pub fn failure_body(&self) -> Option<&str> {
+        match &self.result {
+            TestResult::Failure { cause, .. } => cause.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// The `type` of an erroneous `TestCase`, `None` for other results
+    #[must_use]
+    pub fn error_type(&self) -> Option<&str> {
+        match &self.result {
+            TestResult::Error { type_, .. } => Some(type_),
+            _ => None,
+        }
+    }
+
+    /// The `message` of an erroneous `TestCase`, `None` for other results
+    #[must_use]
+    pub fn error_message(&self) -> Option<&str> {
+        match &self.result {
+            TestResult::Error { message, .. } => Some(message),
+            _ => None,
+        }
+    }
+
+    /// The trace of an erroneous `TestCase`, `None` for other results or if no trace is set
+    #[must_use]
+    pub fn error_body(&self) -> Option<&str> {
+        match &self.result {
+            TestResult::Error { cause, .. } => cause.as_deref(),
+            _ => None,
+        }
     }
 
     /// Create a new ignored `TestCase`
@@ -255,27 +1252,77 @@ impl TestCase {
         TestCase {
             name: name.into(),
             time: Duration::ZERO,
-            result: TestResult::Skipped,
+            result: TestResult::Skipped { message: None },
             classname: None,
             filepath: None,
-            system_out: None,
-            system_err: None,
+            line: None,
+            system_out: Vec::new(),
+            system_err: Vec::new(),
+            assertions: None,
+            metadata: BTreeMap::new(),
+            hostname: None,
+            group: None,
+            timestamp: None,
+            properties: Vec::new(),
+            additional_results: Vec::new(),
         }
     }
 
     /// Check if a `TestCase` ignored
+    #[must_use]
     pub fn is_skipped(&self) -> bool {
-        matches!(self.result, TestResult::Skipped)
+        self.status() == Status::Skipped
+    }
+
+    /// Create a new ignored `TestCase` with the reason why it was skipped
+    pub fn skipped_with_message(name: &str, message: &str) -> Self {
+        TestCase {
+            result: TestResult::Skipped {
+                message: Some(message.into()),
+            },
+            ..TestCase::skipped(name)
+        }
+    }
+
+    /// Create a new disabled `TestCase`, see [`TestResult::Disabled`]
+    pub fn disabled(name: &str) -> Self {
+        TestCase {
+            result: TestResult::Disabled,
+            ..TestCase::skipped(name)
+        }
+    }
+
+    /// Check if a `TestCase` is disabled
+    #[must_use]
+    pub fn is_disabled(&self) -> bool {
+        self.status() == Status::Disabled
+    }
+
+    /// Check if a `TestCase` is erroneous or failed
+    #[must_use]
+    pub fn is_problem(&self) -> bool {
+        self.result.is_problem()
+    }
+
+    /// The [`Status`] of the `TestCase`
+    #[must_use]
+    pub fn status(&self) -> Status {
+        self.result.status()
     }
 }
 
 ///  Builder for [`TestCase`](struct.TestCase.html) objects.
-#[derive(Debug, Clone, Getters)]
+#[derive(Debug, Clone)]
 pub struct TestCaseBuilder {
     pub testcase: TestCase,
 }
 
 impl TestCaseBuilder {
+    /// The `TestCase` being built
+    pub fn testcase(&self) -> &TestCase {
+        &self.testcase
+    }
+
     /// Creates a new TestCaseBuilder for a successful `TestCase`
     pub fn success(name: &str, time: Duration) -> Self {
         TestCaseBuilder {
@@ -283,6 +1330,11 @@ impl TestCaseBuilder {
         }
     }
 
+    /// Creates a new TestCaseBuilder to edit an existing `TestCase`
+    pub fn from_testcase(testcase: TestCase) -> Self {
+        TestCaseBuilder { testcase }
+    }
+
     /// Set the `classname` for the `TestCase`
     pub fn set_classname(&mut self, classname: &str) -> &mut Self {
         self.testcase.classname = Some(classname.to_owned());
@@ -295,15 +1347,93 @@ impl TestCaseBuilder {
         self
     }
 
+    /// Set the `line` in `file` where the `TestCase` is defined
+    pub fn set_line(&mut self, line: u64) -> &mut Self {
+        self.testcase.set_line(line);
+        self
+    }
+
+    /// Set the `hostname` of the `TestCase`, see [`TestCase::set_hostname`]
+    pub fn set_hostname(&mut self, hostname: &str) -> &mut Self {
+        self.testcase.set_hostname(hostname);
+        self
+    }
+
+    /// Set the `group` of the `TestCase`, see [`TestCase::set_group`]
+    pub fn set_group(&mut self, group: &str) -> &mut Self {
+        self.testcase.set_group(group);
+        self
+    }
+
+    /// Add a [`Property`](struct.Property.html) to the `TestCase`, see [`TestCase::add_property`]
+    pub fn add_property(&mut self, name: &str, value: &str) -> &mut Self {
+        self.testcase.add_property(name, value);
+        self
+    }
+
+    /// Add a failure to the `TestCase`, see [`TestCase::add_failure`]
+    pub fn add_failure(&mut self, type_: &str, message: &str) -> &mut Self {
+        self.testcase.add_failure(type_, message);
+        self
+    }
+
+    /// Add an error to the `TestCase`, see [`TestCase::add_error`]
+    pub fn add_error(&mut self, type_: &str, message: &str) -> &mut Self {
+        self.testcase.add_error(type_, message);
+        self
+    }
+
+    /// Set the number of assertions executed by the `TestCase`
+    pub fn set_assertions(&mut self, assertions: u64) -> &mut Self {
+        self.testcase.set_assertions(assertions);
+        self
+    }
+
+    /// Set the time the `TestCase` started
+    pub fn set_timestamp(&mut self, timestamp: OffsetDateTime) -> &mut Self {
+        self.testcase.set_timestamp(timestamp);
+        self
+    }
+
+    /// Add a `metadata` entry to the `TestCase`, see [`TestCase::add_metadata`]
+    pub fn add_metadata(&mut self, key: &str, value: &str) -> &mut Self {
+        self.testcase.add_metadata(key, value);
+        self
+    }
+
     /// Set the `system_out` for the `TestCase`
     pub fn set_system_out(&mut self, system_out: &str) -> &mut Self {
-        self.testcase.system_out = Some(system_out.to_owned());
+        self.testcase.system_out = vec![system_out.to_owned()];
         self
     }
 
     /// Set the `system_err` for the `TestCase`
     pub fn set_system_err(&mut self, system_err: &str) -> &mut Self {
-        self.testcase.system_err = Some(system_err.to_owned());
+        self.testcase.system_err = vec![system_err.to_owned()];
+        self
+    }
+
+    /// Add another `<system-out>` element to the `TestCase`
+    pub fn add_system_out(&mut self, system_out: &str) -> &mut Self {
+        self.testcase.add_system_out(system_out);
+        self
+    }
+
+    /// Add another `<system-err>` element to the `TestCase`
+    pub fn add_system_err(&mut self, system_err: &str) -> &mut Self {
+        self.testcase.add_system_err(system_err);
+        self
+    }
+
+    /// Append a chunk to the last `system_out` of the `TestCase`
+    pub fn append_system_out(&mut self, chunk: &str) -> &mut Self {
+        self.testcase.append_system_out(chunk);
+        self
+    }
+
+    /// Append a chunk to the last `system_err` of the `TestCase`
+    pub fn append_system_err(&mut self, chunk: &str) -> &mut Self {
+        self.testcase.append_system_err(chunk);
         self
     }
 
@@ -319,6 +1449,44 @@ impl TestCaseBuilder {
         self
     }
 
+    /// Add an error that occurred at `file` and `line` to the `TestCase`, see
+    /// [`TestCase::add_error`]
+    ///
+    /// `file` and `line` are written as attributes of the `<error>` element. This is an
+    /// extension to the JUnit schema which is used e.g. for GitHub Actions annotations.
+    pub fn add_error_at(&mut self, type_: &str, message: &str, file: &str, line: u64) -> &mut Self {
+        self.testcase.add_problem(TestResult::Error {
+            type_: type_.into(),
+            message: message.into(),
+            cause: None,
+            file: Some(file.into()),
+            line: Some(line),
+        });
+        self
+    }
+
+    /// Add a failure that occurred at `file` and `line` to the `TestCase`, see
+    /// [`TestCase::add_failure`]
+    ///
+    /// `file` and `line` are written as attributes of the `<failure>` element. This is an
+    /// extension to the JUnit schema which is used e.g. for GitHub Actions annotations.
+    pub fn add_failure_at(
+        &mut self,
+        type_: &str,
+        message: &str,
+        file: &str,
+        line: u64,
+    ) -> &mut Self {
+        self.testcase.add_problem(TestResult::Failure {
+            type_: type_.into(),
+            message: message.into(),
+            cause: None,
+            file: Some(file.into()),
+            line: Some(line),
+        });
+        self
+    }
+
     /// Creates a new TestCaseBuilder for an erroneous `TestCase`
     ///
     /// An erroneous `TestCase` is one that encountered an unexpected error condition.
@@ -346,12 +1514,37 @@ impl TestCaseBuilder {
         }
     }
 
+    /// Creates a new TestCaseBuilder for an ignored `TestCase` with the reason why it was skipped
+    pub fn skipped_with_message(name: &str, message: &str) -> Self {
+        TestCaseBuilder {
+            testcase: TestCase::skipped_with_message(name, message),
+        }
+    }
+
+    /// Creates a new TestCaseBuilder for a disabled `TestCase`
+    pub fn disabled(name: &str) -> Self {
+        TestCaseBuilder {
+            testcase: TestCase::disabled(name),
+        }
+    }
+
     /// Build and return a [`TestCase`](struct.TestCase.html) object based on the data stored in this TestCaseBuilder object.
+    #[must_use]
     pub fn build(&self) -> TestCase {
         self.testcase.clone()
     }
 }
 
-// Make sure the readme is tested too
-#[cfg(doctest)]
-doc_comment::doctest!("../README.md");
+impl From<TestCase> for TestCaseBuilder {
+    fn from(testcase: TestCase) -> Self {
+        TestCaseBuilder::from_testcase(testcase)
+    }
+}
+
+/// Append `chunk` to the last output block, starting a new one if there is none.
+fn append(output: &mut Vec<String>, chunk: &str) {
+    match output.last_mut() {
+        Some(last) => last.push_str(chunk),
+        None => output.push(chunk.to_owned()),
+    }
+}
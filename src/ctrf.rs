@@ -0,0 +1,128 @@
+/*
+ * Copyright (c) 2018 Pascal Bach
+ * Copyright (c) 2021 Siemens Mobility GmbH
+ *
+ * SPDX-License-Identifier:     MIT
+ */
+
+//! Output in the [Common Test Report Format](https://ctrf.io) (CTRF).
+
+use std::io::{self, Write};
+
+use serde_json::{json, Map, Value};
+use time::Duration;
+
+use crate::{Error, Report, Status, TestCase, TestResult, TestSuite};
+
+impl Report {
+    /// Write the Report as [CTRF](https://ctrf.io) JSON to the given `Writer`.
+    ///
+    /// Every test case becomes an entry of `results.tests` with the following mapping:
+    ///
+    /// | CTRF       | Source                                                    |
+    /// |------------|-----------------------------------------------------------|
+    /// | `name`     | test case name                                            |
+    /// | `status`   | `passed` for success, `failed` for failure and error, `skipped` for skipped |
+    /// | `duration` | test case time in milliseconds                            |
+    /// | `message`  | message of the failure or error                           |
+    /// | `trace`    | trace of the failure or error                             |
+    /// | `suite`    | test suite name                                           |
+    /// | `filePath` | test case `file`                                          |
+    /// | `line`     | test case `line`                                          |
+    ///
+    /// `results.summary.start` is the earliest suite timestamp and `stop` the latest
+    /// suite timestamp plus the suite time, both in milliseconds since the Unix epoch.
+    /// Suites without timestamp are not taken into account, a `stop` beyond the
+    /// year 9999 is clamped to its end.
+    pub fn write_ctrf_json<W: Write>(&self, sink: W) -> Result<(), Error> {
+        let summary = self.summary();
+        let start = self
+            .testsuites()
+            .iter()
+            .filter_map(|ts| ts.timestamp)
+            .map(|timestamp| unix_millis(timestamp.unix_timestamp_nanos()))
+            .min()
+            .unwrap_or(0);
+        let stop = self
+            .testsuites()
+            .iter()
+            .filter_map(|ts| Some(ts.timestamp?.saturating_add(ts.time())))
+            .map(|stop| unix_millis(stop.unix_timestamp_nanos()))
+            .max()
+            .unwrap_or(0);
+
+        let tests: Vec<Value> = self
+            .testsuites()
+            .iter()
+            .flat_map(|ts| ts.testcases.iter().map(move |tc| ctrf_test(ts, tc)))
+            .collect();
+
+        let ctrf = json!({
+            "results": {
+                "tool": { "name": env!("CARGO_PKG_NAME") },
+                "summary": {
+                    "tests": summary.tests,
+                    "passed": summary.tests
+                        - summary.failures
+                        - summary.errors
+                        - summary.skipped
+                        - summary.disabled,
+                    "failed": summary.failures + summary.errors,
+                    "pending": 0,
+                    "skipped": summary.skipped + summary.disabled,
+                    "other": 0,
+                    "start": start,
+                    "stop": stop,
+                },
+                "tests": tests,
+            }
+        });
+
+        serde_json::to_writer(sink, &ctrf).map_err(|e| Error::Io(io::Error::from(e)))
+    }
+}
+
+/// Map a single [`TestCase`] to a CTRF test object.
+fn ctrf_test(ts: &TestSuite, tc: &TestCase) -> Value {
+    let mut test = Map::new();
+    let status = match tc.status() {
+        Status::Passed => "passed",
+        Status::Skipped | Status::Disabled => "skipped",
+        Status::Errored | Status::Failed => "failed",
+    };
+    test.insert("name".into(), tc.name.clone().into());
+    test.insert("status".into(), status.into());
+    test.insert("duration".into(), millis(tc.time).into());
+    match &tc.result {
+        TestResult::Error { message, cause, .. } | TestResult::Failure { message, cause, .. } => {
+            test.insert("message".into(), message.clone().into());
+            if let Some(cause) = cause {
+                test.insert("trace".into(), cause.clone().into());
+            }
+        }
+        TestResult::Skipped {
+            message: Some(message),
+        } => {
+            test.insert("message".into(), message.clone().into());
+        }
+        TestResult::Success | TestResult::Skipped { .. } | TestResult::Disabled => {}
+    }
+    test.insert("suite".into(), ts.name.clone().into());
+    if let Some(filepath) = &tc.filepath {
+        test.insert("filePath".into(), filepath.clone().into());
+    }
+    if let Some(line) = tc.line {
+        test.insert("line".into(), line.into());
+    }
+    Value::Object(test)
+}
+
+/// Convert a [`Duration`] into whole milliseconds.
+fn millis(duration: Duration) -> i64 {
+    duration.whole_milliseconds() as i64
+}
+
+/// Convert nanoseconds since the Unix epoch into milliseconds.
+fn unix_millis(nanos: i128) -> i64 {
+    (nanos / 1_000_000) as i64
+}
@@ -0,0 +1,63 @@
+/*
+ * Copyright (c) 2018 Pascal Bach
+ * Copyright (c) 2021 Siemens Mobility GmbH
+ *
+ * SPDX-License-Identifier:     MIT
+ */
+
+use time::Duration;
+
+/// Create a [`Duration`] from a number of seconds.
+///
+/// Values too large to be represented, including infinity, are saturated to
+/// [`Duration::MIN`] or [`Duration::MAX`], NaN results in [`Duration::ZERO`].
+///
+/// ```rust
+/// use junit_report::{secs, Duration, TestCase};
+///
+/// assert_eq!(secs(1.5), Duration::milliseconds(1500));
+/// assert_eq!(secs(0.001), Duration::milliseconds(1));
+/// assert_eq!(secs(f64::INFINITY), Duration::MAX);
+/// assert_eq!(secs(f64::NEG_INFINITY), Duration::MIN);
+/// assert_eq!(secs(f64::NAN), Duration::ZERO);
+///
+/// let tc = TestCase::success("t", secs(1.5));
+/// ```
+pub fn secs(seconds: f64) -> Duration {
+    Duration::saturating_seconds_f64(seconds)
+}
+
+/// Create a [`Duration`] from a number of milliseconds.
+///
+/// ```rust
+/// use junit_report::{millis, secs, Duration, TestCase};
+///
+/// assert_eq!(millis(1500), secs(1.5));
+/// assert_eq!(millis(-250), Duration::milliseconds(-250));
+///
+/// let tc = TestCase::success("t", millis(15));
+/// ```
+pub fn millis(milliseconds: i64) -> Duration {
+    Duration::milliseconds(milliseconds)
+}
+
+/// Create a [`Duration`] from a [`std::time::Duration`], e.g. measured with
+/// [`std::time::Instant`].
+///
+/// The nanosecond precision is kept, durations too long to be represented are
+/// saturated to [`Duration::MAX`].
+///
+/// ```rust
+/// use junit_report::{from_std, millis, Duration, TestCase};
+/// use std::time::Instant;
+///
+/// assert_eq!(from_std(std::time::Duration::from_millis(1500)), millis(1500));
+/// assert_eq!(from_std(std::time::Duration::from_nanos(1)), Duration::nanoseconds(1));
+/// assert_eq!(from_std(std::time::Duration::MAX), Duration::MAX);
+///
+/// let start = Instant::now();
+/// let tc = TestCase::success("t", from_std(start.elapsed()));
+/// ```
+pub fn from_std(duration: std::time::Duration) -> Duration {
+    Duration::try_from(duration).unwrap_or(Duration::MAX)
+}
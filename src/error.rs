@@ -0,0 +1,157 @@
+/*
+ * Copyright (c) 2018 Pascal Bach
+ * Copyright (c) 2021 Siemens Mobility GmbH
+ *
+ * SPDX-License-Identifier:     MIT
+ */
+
+use std::{fmt, io};
+
+/// Errors that can occur while reading or writing a [`Report`](struct.Report.html).
+#[derive(Debug)]
+pub enum Error {
+    /// Error reported by the underlying XML reader or writer.
+    Xml(quick_xml::Error),
+    /// The XML is well-formed but does not describe a valid report, e.g. because
+    /// of a malformed `time` attribute.
+    Parse(String),
+    /// I/O error while accessing a file or directory.
+    Io(io::Error),
+    /// The report is missing required data, see
+    /// [`ReportOptions::strict`](struct.ReportOptions.html#structfield.strict), or contains
+    /// a timestamp that cannot be written.
+    Validation(String),
+    /// Error reported by the underlying XML writer while writing a specific
+    /// [`TestSuite`](struct.TestSuite.html) or [`TestCase`](struct.TestCase.html).
+    Write {
+        /// Position of the `TestSuite` in the report.
+        suite_index: usize,
+        /// Position of the `TestCase` in the suite, `None` if the error happened
+        /// outside of a test case.
+        testcase_index: Option<usize>,
+        /// The error reported by the XML writer.
+        source: quick_xml::Error,
+    },
+}
+
+impl Error {
+    /// Attach the position inside the report to an [`Error::Xml`].
+    ///
+    /// Errors that already carry a position are returned unchanged.
+    pub(crate) fn at(self, suite_index: usize, testcase_index: Option<usize>) -> Self {
+        match self {
+            Error::Xml(source) => Error::Write {
+                suite_index,
+                testcase_index,
+                source,
+            },
+            e => e,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Xml(e) => write!(f, "{}", e),
+            Error::Parse(msg) => write!(f, "invalid report: {}", msg),
+            Error::Io(e) => write!(f, "{}", e),
+            Error::Validation(msg) => write!(f, "validation failed: {}", msg),
+            Error::Write {
+                suite_index,
+                testcase_index: Some(testcase_index),
+                source,
+            } => write!(
+                f,
+                "failed to write testcase {} of testsuite {}: {}",
+                testcase_index, suite_index, source
+            ),
+            Error::Write {
+                suite_index,
+                testcase_index: None,
+                source,
+            } => write!(f, "failed to write testsuite {}: {}", suite_index, source),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Xml(e) => Some(e),
+            Error::Parse(_) | Error::Validation(_) => None,
+            Error::Io(e) => Some(e),
+            Error::Write { source, .. } => Some(source),
+        }
+    }
+}
+
+/// A non-fatal adjustment of the content made while writing a report, see
+/// [`Report::write_xml_with_warnings`](struct.Report.html#method.write_xml_with_warnings).
+///
+/// The indices refer to the position of the [`TestSuite`](struct.TestSuite.html) in the
+/// report and of the [`TestCase`](struct.TestCase.html) in the suite, `None` for content
+/// of the report or suite itself.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum WriteWarning {
+    /// A negative time was written as zero or a time was clamped to
+    /// [`ReportOptions::max_time`](struct.ReportOptions.html#structfield.max_time).
+    ClampedTime {
+        suite_index: Option<usize>,
+        testcase_index: Option<usize>,
+    },
+    /// Characters that are not allowed in XML 1.0, e.g. control characters, were removed.
+    StrippedCharacters {
+        suite_index: Option<usize>,
+        testcase_index: Option<usize>,
+    },
+}
+
+impl WriteWarning {
+    /// Index of the test suite and test case the warning refers to.
+    pub(crate) fn position(&self) -> (Option<usize>, Option<usize>) {
+        match *self {
+            WriteWarning::ClampedTime {
+                suite_index,
+                testcase_index,
+            }
+            | WriteWarning::StrippedCharacters {
+                suite_index,
+                testcase_index,
+            } => (suite_index, testcase_index),
+        }
+    }
+}
+
+impl fmt::Display for WriteWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (what, suite_index, testcase_index) = match self {
+            WriteWarning::ClampedTime {
+                suite_index,
+                testcase_index,
+            } => ("clamped time", suite_index, testcase_index),
+            WriteWarning::StrippedCharacters {
+                suite_index,
+                testcase_index,
+            } => ("stripped invalid characters", suite_index, testcase_index),
+        };
+        match (suite_index, testcase_index) {
+            (Some(s), Some(t)) => write!(f, "{} of testcase {} of testsuite {}", what, t, s),
+            (Some(s), None) => write!(f, "{} of testsuite {}", what, s),
+            _ => write!(f, "{} of report", what),
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Error::Io(e)
+    }
+}
+
+impl From<quick_xml::Error> for Error {
+    fn from(e: quick_xml::Error) -> Self {
+        Error::Xml(e)
+    }
+}
@@ -0,0 +1,144 @@
+/*
+ * Copyright (c) 2018 Pascal Bach
+ * Copyright (c) 2021 Siemens Mobility GmbH
+ *
+ * SPDX-License-Identifier:     MIT
+ */
+
+//! Output in the event based XML format of
+//! [Open Test Reporting](https://github.com/ota4j-team/open-test-reporting), as written by JUnit 5.
+
+use std::io::Write;
+
+use quick_xml::{
+    events::{BytesDecl, BytesText, Event},
+    Writer,
+};
+use time::OffsetDateTime;
+
+use crate::{reports::format_timestamp, Dialect, Error, Report, Status, TestResult};
+
+const CORE_NAMESPACE: &str = "https://schemas.opentest4j.org/reporting/core/0.2.0";
+const EVENTS_NAMESPACE: &str = "https://schemas.opentest4j.org/reporting/events/0.2.0";
+
+impl Report {
+    /// Write the Report as Open Test Reporting events to the given `Writer`.
+    ///
+    /// Every test suite and test case is identified by a running number as `id`,
+    /// test cases refer to their suite as `parentId`:
+    ///
+    /// | Source                 | Events                                                    |
+    /// |------------------------|-----------------------------------------------------------|
+    /// | test suite             | `<e:started>` at the suite timestamp, `<e:finished>` after the suite time |
+    /// | executed test case     | `<e:started>` and `<e:finished>` after the test case time |
+    /// | skipped or disabled test case | `<e:reported>`, as the test case was never started |
+    ///
+    /// The test cases of a suite are assumed to run one after another, starting at the
+    /// suite timestamp, or the Unix epoch for suites without timestamp, times beyond the
+    /// year 9999 are clamped to its end. Each `<e:finished>` and `<e:reported>` event contains a `<result>`
+    /// with status `SUCCESSFUL`, `SKIPPED`, `FAILED` or `ERRORED` and the message of
+    /// failures and errors as `<reason>`. Test suites are always `SUCCESSFUL`.
+    ///
+    /// Traces, output, properties, class names and files have no equivalent in the core
+    /// schema and are not written.
+    pub fn write_open_test_reporting<W: Write>(&self, sink: W) -> Result<(), Error> {
+        let mut writer = Writer::new(sink);
+        writer.write_event(Event::Decl(BytesDecl::new("1.0", Some("UTF-8"), None)))?;
+
+        let mut ids = (1..).map(|id: usize| id.to_string());
+        writer
+            .create_element("e:events")
+            .with_attributes([("xmlns", CORE_NAMESPACE), ("xmlns:e", EVENTS_NAMESPACE)])
+            .write_inner_content(|w| {
+                for ts in self.testsuites() {
+                    let suite_id = ids.next().unwrap();
+                    let timestamp = ts.timestamp.unwrap_or(OffsetDateTime::UNIX_EPOCH);
+                    write_event(w, "e:started", &suite_id, None, &ts.name, timestamp, None)?;
+
+                    let mut time = timestamp;
+                    for tc in &ts.testcases {
+                        let id = ids.next().unwrap();
+                        let parent = Some(suite_id.as_str());
+                        if matches!(tc.status(), Status::Skipped | Status::Disabled) {
+                            write_event(
+                                w,
+                                "e:reported",
+                                &id,
+                                parent,
+                                &tc.name,
+                                time,
+                                Some(&tc.result),
+                            )?;
+                            continue;
+                        }
+                        write_event(w, "e:started", &id, parent, &tc.name, time, None)?;
+                        time = time.saturating_add(tc.time);
+                        write_event(w, "e:finished", &id, None, "", time, Some(&tc.result))?;
+                    }
+
+                    let time = timestamp.saturating_add(ts.time());
+                    write_event(
+                        w,
+                        "e:finished",
+                        &suite_id,
+                        None,
+                        "",
+                        time,
+                        Some(&TestResult::Success),
+                    )?;
+                }
+                Ok::<_, Error>(())
+            })?;
+        Ok(())
+    }
+}
+
+/// Write a single event, `name` is omitted if empty and `result` is written as child.
+fn write_event<W: Write>(
+    w: &mut Writer<W>,
+    event: &str,
+    id: &str,
+    parent_id: Option<&str>,
+    name: &str,
+    time: OffsetDateTime,
+    result: Option<&TestResult>,
+) -> Result<(), Error> {
+    let time = format_timestamp(time, Dialect::Default)?;
+    let element = w.create_element(event).with_attributes(
+        [
+            Some(("id", id)),
+            parent_id.map(|p| ("parentId", p)),
+            Some(("name", name)).filter(|_| !name.is_empty()),
+            Some(("time", time.as_str())),
+        ]
+        .into_iter()
+        .flatten(),
+    );
+    let Some(result) = result else {
+        element.write_empty()?;
+        return Ok(());
+    };
+
+    let (status, reason) = match result {
+        TestResult::Success => ("SUCCESSFUL", None),
+        TestResult::Skipped { message } => ("SKIPPED", message.as_ref()),
+        TestResult::Disabled => ("SKIPPED", None),
+        TestResult::Failure { message, .. } => ("FAILED", Some(message)),
+        TestResult::Error { message, .. } => ("ERRORED", Some(message)),
+    };
+    element.write_inner_content(|w| {
+        let result = w
+            .create_element("result")
+            .with_attribute(("status", status));
+        match reason {
+            Some(reason) => result.write_inner_content(|w| {
+                w.create_element("reason")
+                    .write_text_content(BytesText::new(reason))
+                    .map(drop)
+            }),
+            None => result.write_empty(),
+        }
+        .map(drop)
+    })?;
+    Ok(())
+}
@@ -46,23 +46,92 @@
 //!
 //!     r.write_xml(&mut out).unwrap();
 //! ```
+//!
+//! ## Parallel test runs
+//!
+//! All types are `Send` and `Sync`, so results can be collected from several threads,
+//! e.g. in a `Mutex<Report>`. Alternatively completed test cases can be sent together
+//! with the name of their suite over a channel and grouped with [`Report::from_grouped`]:
+//!
+//! ```rust
+//!     use std::{sync::mpsc, thread};
+//!     use junit_report::{Duration, Report, TestCase};
+//!
+//!     let (tx, rx) = mpsc::channel();
+//!
+//!     let workers: Vec<_> = ["ts1", "ts2"]
+//!         .into_iter()
+//!         .map(|suite| {
+//!             let tx = tx.clone();
+//!             thread::spawn(move || {
+//!                 let testcase = TestCase::success("good test", Duration::seconds(1));
+//!                 tx.send((suite.to_string(), testcase)).unwrap();
+//!             })
+//!         })
+//!         .collect();
+//!     drop(tx);
+//!
+//!     for worker in workers {
+//!         worker.join().unwrap();
+//!     }
+//!
+//!     let r = Report::from_grouped(rx);
+//!     assert_eq!(r.summary().tests, 2);
+//! ```
+//!
+//! ## Comparing reports
+//!
+//! [`Report`], [`TestSuite`], [`TestCase`], [`TestResult`], [`Property`] and [`Summary`]
+//! implement `Eq`, as all of their fields do, including [`Duration`] and
+//! [`OffsetDateTime`]. This allows to compare a parsed report with the expected one
+//! in tests or to deduplicate test cases. All fields are compared, but timestamps are
+//! equal if they describe the same instant, even with a different UTC offset.
+//!
+//! ```rust
+//!     use junit_report::{datetime, Report, ReportBuilder, TestSuiteBuilder};
+//!
+//!     let r = ReportBuilder::new()
+//!         .add_testsuite(TestSuiteBuilder::new("ts1").set_timestamp(datetime!(1970-01-01 01:01 UTC)).build())
+//!         .build();
+//!     let parsed = Report::from_reader(r.to_xml_string().unwrap().as_bytes()).unwrap();
+//!     assert_eq!(parsed, r);
+//! ```
 
+#[cfg(feature = "chrono")]
+mod chrono;
 mod collections;
+#[cfg(feature = "ctrf")]
+mod ctrf;
+mod duration;
+mod error;
+#[cfg(feature = "junit5")]
+mod junit5;
+mod macros;
+mod options;
+mod parser;
 mod reports;
+mod schema;
 
-pub use quick_xml::Error;
 pub use time::{macros::datetime, Duration, OffsetDateTime};
 
 pub use crate::{
-    collections::{TestCase, TestCaseBuilder, TestResult, TestSuite, TestSuiteBuilder},
+    collections::{
+        Property, Status, Summary, TestCase, TestCaseBuilder, TestResult, TestSuite,
+        TestSuiteBuilder,
+    },
+    duration::{from_std, millis, secs},
+    error::{Error, WriteWarning},
+    options::{Dialect, LineEnding, ParseOptions, ReportOptions, TestCaseTime, TextMode, TimeUnit},
     reports::{Report, ReportBuilder},
+    schema::{ValidationError, JUNIT_XSD},
 };
 
 #[cfg(test)]
 mod tests {
     use crate::{
-        datetime, Duration, Report, ReportBuilder, TestCase, TestCaseBuilder, TestSuite,
-        TestSuiteBuilder,
+        datetime, Duration, Error, LineEnding, ParseOptions, Report, ReportBuilder, ReportOptions,
+        Status, Summary, TestCase, TestCaseBuilder, TestCaseTime, TestSuite, TestSuiteBuilder,
+        TextMode, TimeUnit,
     };
     use pretty_assertions::assert_eq;
 
@@ -107,8 +176,8 @@ mod tests {
             "\
 <?xml version=\"1.0\" encoding=\"utf-8\"?>\
 <testsuites>\
-  <testsuite id=\"0\" name=\"ts1\" package=\"testsuite/ts1\" tests=\"0\" errors=\"0\" failures=\"0\" hostname=\"localhost\" timestamp=\"1970-01-01T01:01:00Z\" time=\"0\"/>\
-  <testsuite id=\"1\" name=\"ts2\" package=\"testsuite/ts2\" tests=\"0\" errors=\"0\" failures=\"0\" hostname=\"localhost\" timestamp=\"1970-01-01T01:01:00Z\" time=\"0\"/>\
+  <testsuite id=\"0\" name=\"ts1\" package=\"testsuite/ts1\" tests=\"0\" errors=\"0\" failures=\"0\" skipped=\"0\" hostname=\"localhost\" timestamp=\"1970-01-01T01:01:00Z\" time=\"0\"/>\
+  <testsuite id=\"1\" name=\"ts2\" package=\"testsuite/ts2\" tests=\"0\" errors=\"0\" failures=\"0\" skipped=\"0\" hostname=\"localhost\" timestamp=\"1970-01-01T01:01:00Z\" time=\"0\"/>\
 </testsuites>",
         );
     }
@@ -134,7 +203,7 @@ mod tests {
             "\
 <?xml version=\"1.0\" encoding=\"utf-8\"?>\
 <testsuites>\
-  <testsuite id=\"0\" name=\"ts1\" package=\"testsuite/ts1\" tests=\"0\" errors=\"0\" failures=\"0\" hostname=\"localhost\" timestamp=\"1970-01-01T01:01:00Z\" time=\"0\">\
+  <testsuite id=\"0\" name=\"ts1\" package=\"testsuite/ts1\" tests=\"0\" errors=\"0\" failures=\"0\" skipped=\"0\" hostname=\"localhost\" timestamp=\"1970-01-01T01:01:00Z\" time=\"0\">\
     <system-out><![CDATA[Test sysout]]></system-out>\
   </testsuite>\
 </testsuites>",
@@ -162,7 +231,7 @@ mod tests {
             "\
 <?xml version=\"1.0\" encoding=\"utf-8\"?>\
 <testsuites>\
-  <testsuite id=\"0\" name=\"ts1\" package=\"testsuite/ts1\" tests=\"0\" errors=\"0\" failures=\"0\" hostname=\"localhost\" timestamp=\"1970-01-01T01:01:00Z\" time=\"0\">\
+  <testsuite id=\"0\" name=\"ts1\" package=\"testsuite/ts1\" tests=\"0\" errors=\"0\" failures=\"0\" skipped=\"0\" hostname=\"localhost\" timestamp=\"1970-01-01T01:01:00Z\" time=\"0\">\
     <system-err><![CDATA[Test syserror]]></system-err>\
   </testsuite>\
 </testsuites>",
@@ -194,8 +263,8 @@ mod tests {
             "\
 <?xml version=\"1.0\" encoding=\"utf-8\"?>\
 <testsuites>\
-  <testsuite id=\"0\" name=\"ts1\" package=\"testsuite/ts1\" tests=\"0\" errors=\"0\" failures=\"0\" hostname=\"localhost\" timestamp=\"1970-01-01T01:01:00Z\" time=\"0\"/>\
-  <testsuite id=\"1\" name=\"ts2\" package=\"testsuite/ts2\" tests=\"0\" errors=\"0\" failures=\"0\" hostname=\"localhost\" timestamp=\"1970-01-01T01:01:00Z\" time=\"0\"/>\
+  <testsuite id=\"0\" name=\"ts1\" package=\"testsuite/ts1\" tests=\"0\" errors=\"0\" failures=\"0\" skipped=\"0\" hostname=\"localhost\" timestamp=\"1970-01-01T01:01:00Z\" time=\"0\"/>\
+  <testsuite id=\"1\" name=\"ts2\" package=\"testsuite/ts2\" tests=\"0\" errors=\"0\" failures=\"0\" skipped=\"0\" hostname=\"localhost\" timestamp=\"1970-01-01T01:01:00Z\" time=\"0\"/>\
 </testsuites>",
         );
     }
@@ -289,8 +358,8 @@ mod tests {
             "\
 <?xml version=\"1.0\" encoding=\"utf-8\"?>\
 <testsuites>\
-  <testsuite id=\"0\" name=\"ts1\" package=\"testsuite/ts1\" tests=\"0\" errors=\"0\" failures=\"0\" hostname=\"localhost\" timestamp=\"1970-01-01T01:01:00Z\" time=\"0\"/>\
-  <testsuite id=\"1\" name=\"ts2\" package=\"testsuite/ts2\" tests=\"3\" errors=\"1\" failures=\"1\" hostname=\"localhost\" timestamp=\"1970-01-01T01:01:00Z\" time=\"30.001\">\
+  <testsuite id=\"0\" name=\"ts1\" package=\"testsuite/ts1\" tests=\"0\" errors=\"0\" failures=\"0\" skipped=\"0\" hostname=\"localhost\" timestamp=\"1970-01-01T01:01:00Z\" time=\"0\"/>\
+  <testsuite id=\"1\" name=\"ts2\" package=\"testsuite/ts2\" tests=\"3\" errors=\"1\" failures=\"1\" skipped=\"0\" hostname=\"localhost\" timestamp=\"1970-01-01T01:01:00Z\" time=\"30.001\">\
     <testcase name=\"good test\" time=\"15.001\" classname=\"MyClass\" file=\"./foo.rs\"/>\
     <testcase name=\"error test\" time=\"5\">\
       <error type=\"git error\" message=\"unable to fetch\"/>\
@@ -355,8 +424,8 @@ mod tests {
             "\
 <?xml version=\"1.0\" encoding=\"utf-8\"?>\
 <testsuites>\
-  <testsuite id=\"0\" name=\"ts1\" package=\"testsuite/ts1\" tests=\"0\" errors=\"0\" failures=\"0\" hostname=\"localhost\" timestamp=\"1970-01-01T01:01:00Z\" time=\"0\"/>\
-  <testsuite id=\"1\" name=\"ts2\" package=\"testsuite/ts2\" tests=\"3\" errors=\"1\" failures=\"1\" hostname=\"localhost\" timestamp=\"1970-01-01T01:01:00Z\" time=\"30.001\">\
+  <testsuite id=\"0\" name=\"ts1\" package=\"testsuite/ts1\" tests=\"0\" errors=\"0\" failures=\"0\" skipped=\"0\" hostname=\"localhost\" timestamp=\"1970-01-01T01:01:00Z\" time=\"0\"/>\
+  <testsuite id=\"1\" name=\"ts2\" package=\"testsuite/ts2\" tests=\"3\" errors=\"1\" failures=\"1\" skipped=\"0\" hostname=\"localhost\" timestamp=\"1970-01-01T01:01:00Z\" time=\"30.001\">\
     <testcase name=\"good test\" time=\"15.001\" classname=\"MyClass\" file=\"./foo.rs\">\
       <system-out><![CDATA[Some sysout message]]></system-out>\
     </testcase>\
@@ -425,8 +494,8 @@ mod tests {
             "\
 <?xml version=\"1.0\" encoding=\"utf-8\"?>\
 <testsuites>\
-  <testsuite id=\"0\" name=\"ts1\" package=\"testsuite/ts1\" tests=\"0\" errors=\"0\" failures=\"0\" hostname=\"localhost\" timestamp=\"1970-01-01T01:01:00Z\" time=\"0\"/>\
-  <testsuite id=\"1\" name=\"ts2\" package=\"testsuite/ts2\" tests=\"3\" errors=\"1\" failures=\"1\" hostname=\"localhost\" timestamp=\"1970-01-01T01:01:00Z\" time=\"30.001\">\
+  <testsuite id=\"0\" name=\"ts1\" package=\"testsuite/ts1\" tests=\"0\" errors=\"0\" failures=\"0\" skipped=\"0\" hostname=\"localhost\" timestamp=\"1970-01-01T01:01:00Z\" time=\"0\"/>\
+  <testsuite id=\"1\" name=\"ts2\" package=\"testsuite/ts2\" tests=\"3\" errors=\"1\" failures=\"1\" skipped=\"0\" hostname=\"localhost\" timestamp=\"1970-01-01T01:01:00Z\" time=\"30.001\">\
     <testcase name=\"good test\" time=\"15.001\" classname=\"MyClass\" file=\"./foo.rs\"/>\
     <testcase name=\"error test\" time=\"5\">\
       <error type=\"git error\" message=\"unable to fetch\"><![CDATA[Some error trace]]></error>\
@@ -438,4 +507,3116 @@ mod tests {
 </testsuites>",
         );
     }
+
+    #[test]
+    fn report_from_grouped() {
+        let r = Report::from_grouped(vec![
+            (
+                "ts1".to_string(),
+                TestCase::success("a", Duration::seconds(1)),
+            ),
+            (
+                "ts2".to_string(),
+                TestCase::success("b", Duration::seconds(2)),
+            ),
+            ("ts1".to_string(), TestCase::skipped("c")),
+            (
+                "ts3".to_string(),
+                TestCase::success("d", Duration::seconds(3)),
+            ),
+            ("ts2".to_string(), TestCase::skipped("e")),
+        ]);
+
+        let suites: Vec<(&str, Vec<&str>)> = r
+            .testsuites()
+            .iter()
+            .map(|ts| {
+                (
+                    ts.name.as_str(),
+                    ts.testcases.iter().map(|tc| tc.name.as_str()).collect(),
+                )
+            })
+            .collect();
+
+        assert_eq!(
+            suites,
+            vec![
+                ("ts1", vec!["a", "c"]),
+                ("ts2", vec!["b", "e"]),
+                ("ts3", vec!["d"]),
+            ]
+        );
+    }
+
+    #[test]
+    fn write_error_reports_position() {
+        /// Sink that fails once `remaining` bytes have been written.
+        struct FailingSink {
+            remaining: usize,
+        }
+
+        impl std::io::Write for FailingSink {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                if self.remaining == 0 {
+                    return Err(std::io::Error::other("sink full"));
+                }
+                let n = buf.len().min(self.remaining);
+                self.remaining -= n;
+                Ok(n)
+            }
+
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let timestamp = datetime!(1970-01-01 01:01 UTC);
+
+        let ts1 = TestSuiteBuilder::new("ts1")
+            .set_timestamp(timestamp)
+            .add_testcase(TestCase::success("a", Duration::seconds(1)))
+            .build();
+        let ts2 = TestSuiteBuilder::new("ts2")
+            .set_timestamp(timestamp)
+            .add_testcase(TestCase::success("b", Duration::seconds(1)))
+            .add_testcase(TestCase::success("c", Duration::seconds(1)))
+            .build();
+
+        let r = ReportBuilder::new()
+            .add_testsuite(ts1)
+            .add_testsuite(ts2)
+            .build();
+
+        let mut out: Vec<u8> = Vec::new();
+        r.write_xml(&mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+
+        // Fail in the middle of the second test case of the second suite
+        let remaining = out.find("<testcase name=\"c\"").unwrap() + 5;
+        match r.write_xml(FailingSink { remaining }) {
+            Err(Error::Write {
+                suite_index,
+                testcase_index,
+                ..
+            }) => {
+                assert_eq!(suite_index, 1);
+                assert_eq!(testcase_index, Some(1));
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+
+        // Fail while writing the suite element itself
+        let remaining = out.find("<testsuite id=\"1\"").unwrap() + 5;
+        match r.write_xml(FailingSink { remaining }) {
+            Err(Error::Write {
+                suite_index,
+                testcase_index,
+                ..
+            }) => {
+                assert_eq!(suite_index, 1);
+                assert_eq!(testcase_index, None);
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn bazel_options() {
+        let timestamp = datetime!(1970-01-01 01:01 UTC);
+
+        let ts1 = TestSuiteBuilder::new("ts1")
+            .set_timestamp(timestamp)
+            .add_testcase(TestCase::success("good test", Duration::milliseconds(1500)))
+            .add_testcase(TestCase::failure(
+                "failure test",
+                Duration::seconds(2),
+                "assert_eq",
+                "not equal",
+            ))
+            .add_testcase(TestCase::skipped("skipped test"))
+            .build();
+
+        let mut r = ReportBuilder::new().add_testsuite(ts1).build();
+
+        let mut out: Vec<u8> = Vec::new();
+
+        r.write_xml_with(&mut out, &ReportOptions::bazel()).unwrap();
+
+        // language=xml
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "\
+<?xml version=\"1.0\" encoding=\"utf-8\"?>\
+<testsuites name=\"testsuites\" tests=\"3\" failures=\"1\" errors=\"0\" time=\"3.500\">\
+  <testsuite id=\"0\" name=\"ts1\" package=\"testsuite/ts1\" tests=\"3\" errors=\"0\" failures=\"1\" skipped=\"1\" hostname=\"localhost\" timestamp=\"1970-01-01T01:01:00Z\" time=\"3.500\">\
+    <testcase name=\"good test\" time=\"1.500\" status=\"run\"/>\
+    <testcase name=\"failure test\" time=\"2.000\" status=\"run\">\
+      <failure type=\"assert_eq\" message=\"not equal\"/>\
+    </testcase>\
+    <testcase name=\"skipped test\" time=\"0.000\" status=\"notrun\">\
+      <skipped/>\
+    </testcase>\
+  </testsuite>\
+</testsuites>",
+        );
+
+        r.set_name("all tests");
+        let mut out: Vec<u8> = Vec::new();
+        r.write_xml_with(&mut out, &ReportOptions::bazel()).unwrap();
+        assert!(String::from_utf8(out)
+            .unwrap()
+            .contains(r#"<testsuites name="all tests" tests="3""#));
+    }
+
+    #[test]
+    fn add_property_to_built_testsuite() {
+        let timestamp = datetime!(1970-01-01 01:01 UTC);
+
+        let mut ts1 = TestSuiteBuilder::new("ts1")
+            .set_timestamp(timestamp)
+            .add_property("git.sha", "abc123")
+            .build();
+        ts1.add_property("build.number", "42");
+
+        let r = ReportBuilder::new().add_testsuite(ts1).build();
+
+        let mut out: Vec<u8> = Vec::new();
+
+        r.write_xml(&mut out).unwrap();
+
+        // language=xml
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "\
+<?xml version=\"1.0\" encoding=\"utf-8\"?>\
+<testsuites>\
+  <testsuite id=\"0\" name=\"ts1\" package=\"testsuite/ts1\" tests=\"0\" errors=\"0\" failures=\"0\" skipped=\"0\" hostname=\"localhost\" timestamp=\"1970-01-01T01:01:00Z\" time=\"0\">\
+    <properties>\
+      <property name=\"git.sha\" value=\"abc123\"/>\
+      <property name=\"build.number\" value=\"42\"/>\
+    </properties>\
+  </testsuite>\
+</testsuites>",
+        );
+    }
+
+    #[test]
+    fn sort_testsuites_by_timestamp() {
+        let ts1 = TestSuiteBuilder::new("ts1")
+            .set_timestamp(datetime!(1970-01-01 03:00 UTC))
+            .build();
+        let ts2 = TestSuiteBuilder::new("ts2")
+            .set_timestamp(datetime!(1970-01-01 01:00 UTC))
+            .build();
+        let ts3 = TestSuiteBuilder::new("ts3")
+            .set_timestamp(datetime!(1970-01-01 03:00 UTC))
+            .build();
+        let ts4 = TestSuiteBuilder::new("ts4")
+            .set_timestamp(datetime!(1970-01-01 02:00 UTC))
+            .build();
+
+        let r = ReportBuilder::new()
+            .add_testsuites(vec![ts1, ts2, ts3, ts4])
+            .sort_by_timestamp()
+            .build();
+
+        let mut out: Vec<u8> = Vec::new();
+
+        r.write_xml(&mut out).unwrap();
+
+        // language=xml
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "\
+<?xml version=\"1.0\" encoding=\"utf-8\"?>\
+<testsuites>\
+  <testsuite id=\"0\" name=\"ts2\" package=\"testsuite/ts2\" tests=\"0\" errors=\"0\" failures=\"0\" skipped=\"0\" hostname=\"localhost\" timestamp=\"1970-01-01T01:00:00Z\" time=\"0\"/>\
+  <testsuite id=\"1\" name=\"ts4\" package=\"testsuite/ts4\" tests=\"0\" errors=\"0\" failures=\"0\" skipped=\"0\" hostname=\"localhost\" timestamp=\"1970-01-01T02:00:00Z\" time=\"0\"/>\
+  <testsuite id=\"2\" name=\"ts1\" package=\"testsuite/ts1\" tests=\"0\" errors=\"0\" failures=\"0\" skipped=\"0\" hostname=\"localhost\" timestamp=\"1970-01-01T03:00:00Z\" time=\"0\"/>\
+  <testsuite id=\"3\" name=\"ts3\" package=\"testsuite/ts3\" tests=\"0\" errors=\"0\" failures=\"0\" skipped=\"0\" hostname=\"localhost\" timestamp=\"1970-01-01T03:00:00Z\" time=\"0\"/>\
+</testsuites>",
+        );
+    }
+
+    #[test]
+    fn summary() {
+        let ts1 = TestSuiteBuilder::new("ts1")
+            .add_testcase(TestCase::success("a", Duration::seconds(1)))
+            .add_testcase(TestCase::error("b", Duration::seconds(2), "t", "m"))
+            .add_testcase(TestCase::skipped("c"))
+            .build();
+        let ts2 = TestSuiteBuilder::new("ts2")
+            .add_testcase(TestCase::failure("d", Duration::seconds(3), "t", "m"))
+            .add_testcase(TestCase::failure("e", Duration::seconds(4), "t", "m"))
+            .build();
+
+        for ts in [&ts1, &ts2] {
+            assert_eq!(
+                ts.summary(),
+                Summary {
+                    tests: ts.tests(),
+                    errors: ts.errors(),
+                    failures: ts.failures(),
+                    skipped: ts.skipped(),
+                    disabled: ts.disabled(),
+                    time: ts.time(),
+                    assertions: ts.assertions(),
+                }
+            );
+        }
+
+        let r = ReportBuilder::new().add_testsuites([ts1, ts2]).build();
+
+        assert_eq!(
+            r.summary(),
+            Summary {
+                tests: 5,
+                errors: 1,
+                failures: 2,
+                skipped: 1,
+                disabled: 0,
+                time: Duration::seconds(10),
+                assertions: None,
+            }
+        );
+    }
+
+    #[test]
+    fn append_system_out_and_err() {
+        let mut tc = TestCase::success("good test", Duration::seconds(1));
+
+        tc.append_system_out("first ");
+        tc.append_system_out("second ");
+        tc.append_system_out("third");
+        tc.append_system_err("only");
+
+        assert_eq!(tc.system_out, ["first second third"]);
+        assert_eq!(tc.system_err, ["only"]);
+
+        tc.set_system_out("replaced");
+        assert_eq!(tc.system_out, ["replaced"]);
+
+        let tc = TestCaseBuilder::success("good test", Duration::seconds(1))
+            .set_system_err("a")
+            .append_system_err("b")
+            .build();
+        assert_eq!(tc.system_err, ["ab"]);
+    }
+
+    #[test]
+    fn parse_concatenated_reports() {
+        let timestamp = datetime!(1970-01-01 01:01 UTC);
+
+        let r1 = ReportBuilder::new()
+            .add_testsuite(
+                TestSuiteBuilder::new("ts1")
+                    .set_timestamp(timestamp)
+                    .add_testcase(TestCase::success("a", Duration::seconds(1)))
+                    .build(),
+            )
+            .build();
+        let r2 = ReportBuilder::new()
+            .add_testsuite(
+                TestSuiteBuilder::new("ts2")
+                    .set_timestamp(timestamp)
+                    .add_testcase(
+                        TestCaseBuilder::failure(
+                            "b",
+                            Duration::seconds(2),
+                            "assert_eq",
+                            "not equal",
+                        )
+                        .set_trace("trace")
+                        .build(),
+                    )
+                    .build(),
+            )
+            .add_testsuite(
+                TestSuiteBuilder::new("ts3")
+                    .set_timestamp(timestamp)
+                    .build(),
+            )
+            .build();
+
+        let mut out: Vec<u8> = Vec::new();
+        r1.write_xml(&mut out).unwrap();
+        out.push(b'\n');
+        r2.write_xml(&mut out).unwrap();
+
+        let reports = Report::parse_multi(out.as_slice()).unwrap();
+
+        assert_eq!(reports.len(), 2);
+        for (parsed, expected) in reports.iter().zip([&r1, &r2]) {
+            let mut parsed_xml: Vec<u8> = Vec::new();
+            parsed.write_xml(&mut parsed_xml).unwrap();
+            let mut expected_xml: Vec<u8> = Vec::new();
+            expected.write_xml(&mut expected_xml).unwrap();
+            assert_eq!(
+                String::from_utf8(parsed_xml).unwrap(),
+                String::from_utf8(expected_xml).unwrap()
+            );
+        }
+
+        // A single document still parses with `from_reader`
+        let mut out: Vec<u8> = Vec::new();
+        r2.write_xml(&mut out).unwrap();
+        let parsed = Report::from_reader(out.as_slice()).unwrap();
+        assert_eq!(parsed.testsuites().len(), 2);
+    }
+
+    #[test]
+    fn normalize_filepath() {
+        let timestamp = datetime!(1970-01-01 01:01 UTC);
+
+        let ts1 = TestSuiteBuilder::new("ts1")
+            .set_timestamp(timestamp)
+            .add_testcase(
+                TestCaseBuilder::success("absolute", Duration::ZERO)
+                    .set_filepath("/home/ci/work/src/lib.rs")
+                    .build(),
+            )
+            .add_testcase(
+                TestCaseBuilder::success("outside", Duration::ZERO)
+                    .set_filepath("/usr/src/lib.rs")
+                    .build(),
+            )
+            .add_testcase(
+                TestCaseBuilder::success("windows", Duration::ZERO)
+                    .set_filepath("C:\\work\\tests\\it.rs")
+                    .build(),
+            )
+            .build();
+
+        let r = ReportBuilder::new().add_testsuite(ts1).build();
+
+        let options = ReportOptions {
+            path_prefix_strip: Some("/home/ci/work".into()),
+            ..Default::default()
+        };
+        let mut out: Vec<u8> = Vec::new();
+        r.write_xml_with(&mut out, &options).unwrap();
+        let out = String::from_utf8(out).unwrap();
+
+        assert!(out.contains("<testcase name=\"absolute\" time=\"0\" file=\"src/lib.rs\"/>"));
+        assert!(out.contains("<testcase name=\"outside\" time=\"0\" file=\"/usr/src/lib.rs\"/>"));
+        assert!(out.contains("file=\"C:\\work\\tests\\it.rs\""));
+
+        let options = ReportOptions {
+            path_prefix_strip: Some("C:\\work".into()),
+            path_forward_slashes: true,
+            ..Default::default()
+        };
+        let mut out: Vec<u8> = Vec::new();
+        r.write_xml_with(&mut out, &options).unwrap();
+        let out = String::from_utf8(out).unwrap();
+
+        assert!(out.contains("<testcase name=\"windows\" time=\"0\" file=\"tests/it.rs\"/>"));
+        assert!(out.contains("file=\"/home/ci/work/src/lib.rs\""));
+    }
+
+    #[test]
+    fn failure_with_location() {
+        let timestamp = datetime!(1970-01-01 01:01 UTC);
+
+        let test_failure = TestCaseBuilder::success("failure test", Duration::seconds(10))
+            .add_failure_at("assert_eq", "not equal", "src/lib.rs", 42)
+            .set_trace("Some failure trace")
+            .build();
+        let test_error = TestCaseBuilder::success("error test", Duration::seconds(5))
+            .add_error_at("git error", "unable to fetch", "src/git.rs", 7)
+            .build();
+
+        let ts1 = TestSuiteBuilder::new("ts1")
+            .set_timestamp(timestamp)
+            .add_testcase(test_failure)
+            .add_testcase(test_error)
+            .build();
+
+        let r = ReportBuilder::new().add_testsuite(ts1).build();
+
+        let mut out: Vec<u8> = Vec::new();
+
+        r.write_xml(&mut out).unwrap();
+
+        // language=xml
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "\
+<?xml version=\"1.0\" encoding=\"utf-8\"?>\
+<testsuites>\
+  <testsuite id=\"0\" name=\"ts1\" package=\"testsuite/ts1\" tests=\"2\" errors=\"1\" failures=\"1\" skipped=\"0\" hostname=\"localhost\" timestamp=\"1970-01-01T01:01:00Z\" time=\"15\">\
+    <testcase name=\"failure test\" time=\"10\">\
+      <failure type=\"assert_eq\" message=\"not equal\" file=\"src/lib.rs\" line=\"42\"><![CDATA[Some failure trace]]></failure>\
+    </testcase>\
+    <testcase name=\"error test\" time=\"5\">\
+      <error type=\"git error\" message=\"unable to fetch\" file=\"src/git.rs\" line=\"7\"/>\
+    </testcase>\
+  </testsuite>\
+</testsuites>",
+        );
+    }
+
+    #[test]
+    fn report_is_empty_and_has_tests() {
+        let r = Report::new();
+        assert!(r.is_empty());
+        assert!(!r.has_tests());
+
+        let r = ReportBuilder::new()
+            .add_testsuite(TestSuite::new("ts1"))
+            .add_testsuite(TestSuite::new("ts2"))
+            .build();
+        assert!(!r.is_empty());
+        assert!(!r.has_tests());
+
+        let r = ReportBuilder::new()
+            .add_testsuite(TestSuite::new("ts1"))
+            .add_testsuite(
+                TestSuiteBuilder::new("ts2")
+                    .add_testcase(TestCase::skipped("skipped"))
+                    .build(),
+            )
+            .build();
+        assert!(!r.is_empty());
+        assert!(r.has_tests());
+    }
+
+    #[test]
+    fn edit_parsed_testcase_with_builder() {
+        // language=xml
+        let xml = "\
+<testsuites>\
+  <testsuite name=\"ts1\" timestamp=\"1970-01-01T01:01:00Z\">\
+    <testcase name=\"good test\" time=\"1.5\" classname=\"Old\"/>\
+  </testsuite>\
+</testsuites>";
+
+        let r = Report::from_reader(xml.as_bytes()).unwrap();
+        let ts = r.testsuites()[0].clone();
+        let tc = ts.testcases[0].clone();
+
+        let tc = TestCaseBuilder::from(tc).set_classname("New").build();
+        assert_eq!(tc.classname.as_deref(), Some("New"));
+        assert_eq!(tc.name, "good test");
+        assert_eq!(tc.time, Duration::milliseconds(1500));
+
+        let ts = TestSuiteBuilder::from(ts)
+            .add_testcase(TestCase::skipped("added"))
+            .build();
+        assert_eq!(ts.name, "ts1");
+        assert_eq!(ts.tests(), 2);
+        assert_eq!(ts.skipped(), 1);
+    }
+
+    #[test]
+    fn strict_options() {
+        let strict = ReportOptions {
+            strict: true,
+            ..Default::default()
+        };
+
+        let r = ReportBuilder::new()
+            .add_testsuite(TestSuite::new(""))
+            .build();
+
+        let mut out: Vec<u8> = Vec::new();
+        assert!(matches!(
+            r.write_xml_with(&mut out, &strict),
+            Err(Error::Validation(_))
+        ));
+        assert!(out.is_empty());
+
+        let mut out: Vec<u8> = Vec::new();
+        r.write_xml_with(&mut out, &ReportOptions::default())
+            .unwrap();
+        assert!(String::from_utf8(out).unwrap().contains("name=\"\""));
+
+        let r = ReportBuilder::new()
+            .add_testsuite(
+                TestSuiteBuilder::new("ts1")
+                    .add_testcase(TestCase::success("", Duration::ZERO))
+                    .build(),
+            )
+            .build();
+        assert!(matches!(
+            r.write_xml_with(Vec::new(), &strict),
+            Err(Error::Validation(_))
+        ));
+
+        let r = ReportBuilder::new()
+            .add_testsuite(
+                TestSuiteBuilder::new("ts1")
+                    .add_testcase(TestCase::success("good test", Duration::ZERO))
+                    .build(),
+            )
+            .build();
+        r.write_xml_with(Vec::new(), &strict).unwrap();
+    }
+
+    #[test]
+    fn count_tests_u64() {
+        let ts = TestSuiteBuilder::new("ts")
+            .add_testcase(TestCase::success("a", Duration::ZERO))
+            .add_testcase(TestCase::error("b", Duration::ZERO, "t", "m"))
+            .add_testcase(TestCase::failure("c", Duration::ZERO, "t", "m"))
+            .add_testcase(TestCase::failure("d", Duration::ZERO, "t", "m"))
+            .add_testcase(TestCase::skipped("e"))
+            .build();
+
+        assert_eq!(ts.tests_u64(), 5);
+        assert_eq!(ts.tests_u64(), ts.tests() as u64);
+        assert_eq!(ts.errors_u64(), ts.errors() as u64);
+        assert_eq!(ts.failures_u64(), ts.failures() as u64);
+        assert_eq!(ts.skipped_u64(), ts.skipped() as u64);
+    }
+
+    #[test]
+    fn long_message_to_body() {
+        let timestamp = datetime!(1970-01-01 01:01 UTC);
+
+        let message = format!("{}\n{}", "x".repeat(250), "y".repeat(249));
+        assert_eq!(message.len(), 500);
+
+        let ts1 = TestSuiteBuilder::new("ts1")
+            .set_timestamp(timestamp)
+            .add_testcase(
+                TestCaseBuilder::failure("long", Duration::ZERO, "assert", &message)
+                    .set_trace("trace")
+                    .build(),
+            )
+            .add_testcase(TestCase::failure(
+                "short",
+                Duration::ZERO,
+                "assert",
+                "short",
+            ))
+            .build();
+
+        let r = ReportBuilder::new().add_testsuite(ts1).build();
+
+        let options = ReportOptions {
+            long_message_to_body: Some(20),
+            ..Default::default()
+        };
+        let mut out: Vec<u8> = Vec::new();
+        r.write_xml_with(&mut out, &options).unwrap();
+
+        // language=xml
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            format!(
+                "\
+<?xml version=\"1.0\" encoding=\"utf-8\"?>\
+<testsuites>\
+  <testsuite id=\"0\" name=\"ts1\" package=\"testsuite/ts1\" tests=\"2\" errors=\"0\" failures=\"2\" skipped=\"0\" hostname=\"localhost\" timestamp=\"1970-01-01T01:01:00Z\" time=\"0\">\
+    <testcase name=\"long\" time=\"0\">\
+      <failure type=\"assert\" message=\"xxxxxxxxxxxxxxxxxxxx...\"><![CDATA[{}\ntrace]]></failure>\
+    </testcase>\
+    <testcase name=\"short\" time=\"0\">\
+      <failure type=\"assert\" message=\"short\"/>\
+    </testcase>\
+  </testsuite>\
+</testsuites>",
+                message
+            ),
+        );
+
+        // Without the option the message stays in the attribute
+        let mut out: Vec<u8> = Vec::new();
+        r.write_xml(&mut out).unwrap();
+        assert!(String::from_utf8(out)
+            .unwrap()
+            .contains("<![CDATA[trace]]>"));
+    }
+
+    #[test]
+    fn clear_and_reuse() {
+        let timestamp = datetime!(1970-01-01 01:01 UTC);
+
+        let mut ts = TestSuiteBuilder::new("ts1")
+            .set_timestamp(timestamp)
+            .add_property("run", "1")
+            .add_testcase(TestCase::failure("first", Duration::seconds(1), "t", "m"))
+            .set_system_out("first run")
+            .build();
+
+        ts.clear();
+        assert_eq!(ts.tests(), 0);
+        assert_eq!(ts.name, "ts1");
+        assert_eq!(ts.package, "testsuite/ts1");
+        assert!(ts.properties.is_empty());
+        assert!(ts.system_out.is_empty());
+
+        ts.add_testcase(TestCase::success("second", Duration::seconds(2)));
+
+        let mut r = ReportBuilder::new()
+            .add_testsuite(TestSuite::new("stale"))
+            .build();
+        r.clear();
+        assert!(r.is_empty());
+        r.add_testsuite(ts);
+
+        let mut out: Vec<u8> = Vec::new();
+
+        r.write_xml(&mut out).unwrap();
+
+        // language=xml
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "\
+<?xml version=\"1.0\" encoding=\"utf-8\"?>\
+<testsuites>\
+  <testsuite id=\"0\" name=\"ts1\" package=\"testsuite/ts1\" tests=\"1\" errors=\"0\" failures=\"0\" skipped=\"0\" hostname=\"localhost\" timestamp=\"1970-01-01T01:01:00Z\" time=\"2\">\
+    <testcase name=\"second\" time=\"2\"/>\
+  </testsuite>\
+</testsuites>",
+        );
+    }
+
+    #[test]
+    fn slowest_testcases() {
+        let ts1 = TestSuiteBuilder::new("ts1")
+            .add_testcase(TestCase::success("a", Duration::seconds(3)))
+            .add_testcase(TestCase::success("b", Duration::seconds(10)))
+            .add_testcase(TestCase::skipped("c"))
+            .build();
+        let ts2 = TestSuiteBuilder::new("ts2")
+            .add_testcase(TestCase::failure("e", Duration::seconds(5), "t", "m"))
+            .add_testcase(TestCase::success("d", Duration::seconds(5)))
+            .build();
+
+        let r = ReportBuilder::new().add_testsuites([ts1, ts2]).build();
+
+        let slowest: Vec<(&str, &str)> = r
+            .slowest(3)
+            .into_iter()
+            .map(|(ts, tc)| (ts.name.as_str(), tc.name.as_str()))
+            .collect();
+        assert_eq!(slowest, [("ts1", "b"), ("ts2", "d"), ("ts2", "e")]);
+
+        assert_eq!(r.slowest(10).len(), 5);
+    }
+
+    #[test]
+    fn parse_timestamp_preserves_offset() {
+        // language=xml
+        let xml = "\
+<?xml version=\"1.0\" encoding=\"utf-8\"?>\
+<testsuites>\
+  <testsuite id=\"0\" name=\"ts1\" package=\"testsuite/ts1\" tests=\"0\" errors=\"0\" failures=\"0\" skipped=\"0\" hostname=\"localhost\" timestamp=\"2024-01-01T10:00:00+02:00\" time=\"0\"/>\
+</testsuites>";
+
+        let r = Report::from_reader(xml.as_bytes()).unwrap();
+        let timestamp = r.testsuites()[0].timestamp.unwrap();
+        assert_eq!(timestamp, datetime!(2024-01-01 10:00 +2));
+        assert_eq!(timestamp.offset().whole_hours(), 2);
+
+        let mut out: Vec<u8> = Vec::new();
+        r.write_xml(&mut out).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), xml);
+    }
+
+    #[test]
+    fn failure_and_error_accessors() {
+        let success = TestCase::success("success", Duration::ZERO);
+        let skipped = TestCase::skipped("skipped");
+        let failure = TestCaseBuilder::failure("failure", Duration::ZERO, "assert_eq", "not equal")
+            .set_trace("failure trace")
+            .build();
+        let error = TestCase::error("error", Duration::ZERO, "git error", "unable to fetch");
+
+        for tc in [&success, &skipped] {
+            assert_eq!(tc.failure_type(), None);
+            assert_eq!(tc.failure_message(), None);
+            assert_eq!(tc.failure_body(), None);
+            assert_eq!(tc.error_type(), None);
+            assert_eq!(tc.error_message(), None);
+            assert_eq!(tc.error_body(), None);
+        }
+
+        assert_eq!(failure.failure_type(), Some("assert_eq"));
+        assert_eq!(failure.failure_message(), Some("not equal"));
+        assert_eq!(failure.failure_body(), Some("failure trace"));
+        assert_eq!(failure.error_type(), None);
+        assert_eq!(failure.error_message(), None);
+        assert_eq!(failure.error_body(), None);
+
+        assert_eq!(error.error_type(), Some("git error"));
+        assert_eq!(error.error_message(), Some("unable to fetch"));
+        assert_eq!(error.error_body(), None);
+        assert_eq!(error.failure_type(), None);
+        assert_eq!(error.failure_message(), None);
+        assert_eq!(error.failure_body(), None);
+    }
+
+    #[cfg(feature = "ctrf")]
+    #[test]
+    fn ctrf_json() {
+        let timestamp = datetime!(1970-01-01 00:00:01 UTC);
+
+        let ts1 = TestSuiteBuilder::new("ts1")
+            .set_timestamp(timestamp)
+            .add_testcase(
+                TestCaseBuilder::success("good test", Duration::milliseconds(1500))
+                    .set_filepath("./foo.rs")
+                    .build(),
+            )
+            .add_testcase(
+                TestCaseBuilder::failure(
+                    "failure test",
+                    Duration::seconds(2),
+                    "assert_eq",
+                    "not equal",
+                )
+                .set_trace("trace")
+                .build(),
+            )
+            .add_testcase(TestCase::error(
+                "error test",
+                Duration::ZERO,
+                "git error",
+                "unable to fetch",
+            ))
+            .add_testcase(TestCase::skipped("skipped test"))
+            .build();
+
+        let r = ReportBuilder::new().add_testsuite(ts1).build();
+
+        let mut out: Vec<u8> = Vec::new();
+        r.write_ctrf_json(&mut out).unwrap();
+
+        let json: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        let results = &json["results"];
+
+        assert_eq!(results["tool"]["name"], "junit-report");
+        assert_eq!(results["summary"]["tests"], 4);
+        assert_eq!(results["summary"]["passed"], 1);
+        assert_eq!(results["summary"]["failed"], 2);
+        assert_eq!(results["summary"]["skipped"], 1);
+        assert_eq!(results["summary"]["start"], 1000);
+        assert_eq!(results["summary"]["stop"], 4500);
+
+        let tests = results["tests"].as_array().unwrap();
+        assert_eq!(tests.len(), 4);
+        assert_eq!(tests[0]["name"], "good test");
+        assert_eq!(tests[0]["status"], "passed");
+        assert_eq!(tests[0]["duration"], 1500);
+        assert_eq!(tests[0]["suite"], "ts1");
+        assert_eq!(tests[0]["filePath"], "./foo.rs");
+        assert_eq!(tests[1]["status"], "failed");
+        assert_eq!(tests[1]["message"], "not equal");
+        assert_eq!(tests[1]["trace"], "trace");
+        assert_eq!(tests[2]["status"], "failed");
+        assert_eq!(tests[2]["message"], "unable to fetch");
+        assert_eq!(tests[3]["status"], "skipped");
+        assert!(tests[3].get("message").is_none());
+    }
+
+    #[test]
+    fn empty_report_with_property() {
+        let mut r = Report::new();
+        r.add_property("commit", "abc123");
+
+        let mut out: Vec<u8> = Vec::new();
+        r.write_xml(&mut out).unwrap();
+
+        // language=xml
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "<?xml version=\"1.0\" encoding=\"utf-8\"?><testsuites><properties><property name=\"commit\" value=\"abc123\"/></properties></testsuites>",
+        );
+    }
+
+    #[test]
+    fn validate_against_xsd() {
+        let ts = TestSuiteBuilder::new("ts1")
+            .add_property("commit", "abc123")
+            .add_testcase(TestCase::success(
+                "good test",
+                Duration::milliseconds(15001),
+            ))
+            .add_testcase(
+                TestCaseBuilder::success("failure test", Duration::seconds(1))
+                    .add_failure_at("assert", "nope", "src/lib.rs", 42)
+                    .build(),
+            )
+            .add_testcase(TestCase::skipped("skipped test"))
+            .set_system_out("out")
+            .build();
+        let r = ReportBuilder::new().add_testsuite(ts).build();
+
+        assert_eq!(r.validate_against_xsd(), Ok(()));
+        assert_eq!(Report::new().validate_against_xsd(), Ok(()));
+    }
+
+    #[test]
+    fn validate_xml_counts_and_time() {
+        // language=xml
+        let xml = r#"<testsuites><testsuite name="ts" tests="two" failures="-1" time="1e3"><testcase name="tc" time="1."/></testsuite></testsuites>"#;
+
+        let errors: Vec<String> = crate::schema::validate_xml(xml.as_bytes())
+            .unwrap_err()
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+
+        assert_eq!(
+            errors,
+            [
+                "/testsuites/testsuite[0]: invalid value `two` for attribute `tests`: expected a non-negative integer",
+                "/testsuites/testsuite[0]: invalid value `-1` for attribute `failures`: expected a non-negative integer",
+                "/testsuites/testsuite[0]: invalid value `1e3` for attribute `time`: expected a decimal number",
+            ]
+        );
+    }
+
+    #[test]
+    fn validate_xml_nesting() {
+        // language=xml
+        let xml = r#"<testsuites><testcase name="tc"/><testsuite name="ts" tests="1" foo="bar"><system-out/><testcase/><properties/></testsuite></testsuites>"#;
+
+        let errors: Vec<String> = crate::schema::validate_xml(xml.as_bytes())
+            .unwrap_err()
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+
+        assert_eq!(
+            errors,
+            [
+                "/testsuites: element `testcase` is not allowed in `testsuites`",
+                "/testsuites/testsuite[0]: unexpected attribute `foo`",
+                "/testsuites/testsuite[0]: element `testcase` is out of order",
+                "/testsuites/testsuite[0]: element `properties` is out of order",
+            ]
+        );
+
+        // language=xml
+        let xml = r#"<testsuite name="ts" tests="0"><properties></properties></testsuite>"#;
+        let errors = crate::schema::validate_xml(xml.as_bytes()).unwrap_err();
+        assert_eq!(
+            errors[0].to_string(),
+            "/testsuite/properties[0]: missing `property` element"
+        );
+    }
+
+    #[test]
+    fn suite_extra_time() {
+        let mut ts = TestSuite::new("ts1");
+        ts.set_timestamp(datetime!(1970-01-01 00:00 UTC));
+        ts.add_time(Duration::seconds(2));
+        ts.add_testcase(TestCase::success("first", Duration::seconds(1)));
+        ts.add_time(Duration::milliseconds(500));
+        ts.add_testcase(TestCase::success("second", Duration::seconds(3)));
+
+        assert_eq!(ts.time(), Duration::milliseconds(6500));
+        assert_eq!(ts.summary().time, Duration::milliseconds(6500));
+
+        let r = ReportBuilder::new().add_testsuite(ts.clone()).build();
+        let mut out: Vec<u8> = Vec::new();
+        r.write_xml(&mut out).unwrap();
+        assert!(String::from_utf8(out).unwrap().contains("time=\"6.5\">"));
+
+        ts.set_time(Duration::seconds(10));
+        assert_eq!(ts.time(), Duration::seconds(10));
+        ts.add_time(Duration::seconds(1));
+        assert_eq!(ts.time(), Duration::seconds(11));
+    }
+
+    #[test]
+    fn types_are_send_and_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+
+        assert_send_sync::<Report>();
+        assert_send_sync::<ReportBuilder>();
+        assert_send_sync::<TestSuite>();
+        assert_send_sync::<TestSuiteBuilder>();
+        assert_send_sync::<TestCase>();
+        assert_send_sync::<TestCaseBuilder>();
+        assert_send_sync::<Error>();
+
+        let report = std::sync::Mutex::new(Report::new());
+        std::thread::scope(|s| {
+            for name in ["ts1", "ts2"] {
+                let report = &report;
+                s.spawn(move || report.lock().unwrap().add_testsuite(TestSuite::new(name)));
+            }
+        });
+        assert_eq!(report.into_inner().unwrap().testsuites().len(), 2);
+    }
+
+    #[test]
+    fn has_problems() {
+        let skipped = TestSuiteBuilder::new("skipped")
+            .add_testcase(TestCase::success("good test", Duration::ZERO))
+            .add_testcase(TestCase::skipped("skipped test"))
+            .build();
+        let failed = TestSuiteBuilder::new("failed")
+            .add_testcase(TestCase::failure(
+                "failure test",
+                Duration::ZERO,
+                "assert",
+                "nope",
+            ))
+            .build();
+
+        assert!(!skipped.has_problems());
+        assert!(failed.has_problems());
+        assert!(failed.testcases[0].is_problem());
+        assert!(failed.testcases[0].result.is_problem());
+
+        let mut r = ReportBuilder::new().add_testsuite(skipped).build();
+        assert!(!r.has_problems());
+        r.add_testsuite(failed);
+        assert!(r.has_problems());
+    }
+
+    #[test]
+    fn multiple_system_out() {
+        let tc = TestCaseBuilder::success("good test", Duration::ZERO)
+            .set_system_out("setup")
+            .add_system_out("run")
+            .build();
+        let ts = TestSuiteBuilder::new("ts1")
+            .set_timestamp(datetime!(1970-01-01 00:00 UTC))
+            .add_testcase(tc)
+            .build();
+        let r = ReportBuilder::new().add_testsuite(ts).build();
+
+        let mut out: Vec<u8> = Vec::new();
+        r.write_xml(&mut out).unwrap();
+
+        // language=xml
+        assert_eq!(
+            String::from_utf8(out.clone()).unwrap(),
+            "<?xml version=\"1.0\" encoding=\"utf-8\"?><testsuites><testsuite id=\"0\" name=\"ts1\" package=\"testsuite/ts1\" tests=\"1\" errors=\"0\" failures=\"0\" skipped=\"0\" hostname=\"localhost\" timestamp=\"1970-01-01T00:00:00Z\" time=\"0\"><testcase name=\"good test\" time=\"0\"><system-out><![CDATA[setup]]></system-out><system-out><![CDATA[run]]></system-out></testcase></testsuite></testsuites>",
+        );
+
+        let parsed = Report::from_reader(out.as_slice()).unwrap();
+        assert_eq!(
+            parsed.testsuites()[0].testcases[0].system_out,
+            ["setup", "run"]
+        );
+    }
+
+    #[test]
+    fn pytest_preset() {
+        let ts = TestSuiteBuilder::new("pytest")
+            .set_timestamp(datetime!(1970-01-01 00:00 UTC))
+            .add_testcase(
+                TestCaseBuilder::success("test_fail", Duration::milliseconds(2))
+                    .add_failure_at("AssertionError", "assert 1 == 2", "test_mod.py", 6)
+                    .build(),
+            )
+            .add_testcase(TestCase::skipped("test_skip"))
+            .build();
+        let r = ReportBuilder::new().add_testsuite(ts).build();
+
+        let mut out: Vec<u8> = Vec::new();
+        r.write_xml_with(&mut out, &ReportOptions::pytest())
+            .unwrap();
+
+        // language=xml
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "<?xml version=\"1.0\" encoding=\"utf-8\"?><testsuites><testsuite name=\"pytest\" errors=\"0\" failures=\"1\" skipped=\"1\" tests=\"2\" time=\"0.002\" timestamp=\"1970-01-01T00:00:00Z\" hostname=\"localhost\"><testcase classname=\"\" name=\"test_fail\" time=\"0.002\"><failure message=\"assert 1 == 2\"/></testcase><testcase classname=\"\" name=\"test_skip\" time=\"0.000\"><skipped type=\"pytest.skip\" message=\"\"/></testcase></testsuite></testsuites>",
+        );
+    }
+
+    #[test]
+    fn write_xml_return() {
+        let r = ReportBuilder::new()
+            .add_testsuite(
+                TestSuiteBuilder::new("ts1")
+                    .set_timestamp(datetime!(1970-01-01 00:00 UTC))
+                    .build(),
+            )
+            .build();
+
+        let out = r.write_xml_return(Vec::new()).unwrap();
+
+        // language=xml
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "<?xml version=\"1.0\" encoding=\"utf-8\"?><testsuites><testsuite id=\"0\" name=\"ts1\" package=\"testsuite/ts1\" tests=\"0\" errors=\"0\" failures=\"0\" skipped=\"0\" hostname=\"localhost\" timestamp=\"1970-01-01T00:00:00Z\" time=\"0\"/></testsuites>",
+        );
+    }
+
+    #[test]
+    fn omit_empty_output() {
+        let tc = TestCaseBuilder::success("good test", Duration::ZERO)
+            .set_system_out("")
+            .set_system_err("err")
+            .build();
+        let ts = TestSuiteBuilder::new("ts1")
+            .set_timestamp(datetime!(1970-01-01 00:00 UTC))
+            .add_testcase(tc)
+            .set_system_out("")
+            .build();
+        let r = ReportBuilder::new().add_testsuite(ts).build();
+
+        let mut out: Vec<u8> = Vec::new();
+        r.write_xml(&mut out).unwrap();
+
+        // language=xml
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "<?xml version=\"1.0\" encoding=\"utf-8\"?><testsuites><testsuite id=\"0\" name=\"ts1\" package=\"testsuite/ts1\" tests=\"1\" errors=\"0\" failures=\"0\" skipped=\"0\" hostname=\"localhost\" timestamp=\"1970-01-01T00:00:00Z\" time=\"0\"><testcase name=\"good test\" time=\"0\"><system-out><![CDATA[]]></system-out><system-err><![CDATA[err]]></system-err></testcase><system-out><![CDATA[]]></system-out></testsuite></testsuites>",
+        );
+
+        let options = ReportOptions {
+            omit_empty_output: true,
+            ..Default::default()
+        };
+        let mut out: Vec<u8> = Vec::new();
+        r.write_xml_with(&mut out, &options).unwrap();
+
+        // language=xml
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "<?xml version=\"1.0\" encoding=\"utf-8\"?><testsuites><testsuite id=\"0\" name=\"ts1\" package=\"testsuite/ts1\" tests=\"1\" errors=\"0\" failures=\"0\" skipped=\"0\" hostname=\"localhost\" timestamp=\"1970-01-01T00:00:00Z\" time=\"0\"><testcase name=\"good test\" time=\"0\"><system-err><![CDATA[err]]></system-err></testcase></testsuite></testsuites>",
+        );
+    }
+
+    #[test]
+    fn testcase_macro() {
+        let success = crate::testcase!(success "good test", crate::secs(1.5));
+        assert!(success.is_success());
+        assert_eq!(success.name, "good test");
+        assert_eq!(success.time, Duration::milliseconds(1500));
+
+        let error =
+            crate::testcase!(error "error test", crate::millis(5), "git error", "unable to fetch");
+        assert_eq!(error.error_type(), Some("git error"));
+        assert_eq!(error.error_message(), Some("unable to fetch"));
+        assert_eq!(error.time, Duration::milliseconds(5));
+
+        let failure = crate::testcase!(failure "failure test", Duration::seconds(2), "assert_eq", "not equal");
+        assert_eq!(failure.failure_type(), Some("assert_eq"));
+        assert_eq!(failure.failure_message(), Some("not equal"));
+
+        let skipped = crate::testcase!(skipped "skipped test");
+        assert!(skipped.is_skipped());
+        assert_eq!(skipped.name, "skipped test");
+    }
+
+    #[test]
+    fn max_time() {
+        let ts = TestSuiteBuilder::new("ts1")
+            .set_timestamp(datetime!(1970-01-01 00:00 UTC))
+            .add_testcase(TestCase::success("absurd", Duration::MAX))
+            .build();
+        let r = ReportBuilder::new().add_testsuite(ts).build();
+
+        let options = ReportOptions {
+            max_time: Some(Duration::days(3650)),
+            ..Default::default()
+        };
+        let mut out: Vec<u8> = Vec::new();
+        r.write_xml_with(&mut out, &options).unwrap();
+
+        // language=xml
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "<?xml version=\"1.0\" encoding=\"utf-8\"?><testsuites><testsuite id=\"0\" name=\"ts1\" package=\"testsuite/ts1\" tests=\"1\" errors=\"0\" failures=\"0\" skipped=\"0\" hostname=\"localhost\" timestamp=\"1970-01-01T00:00:00Z\" time=\"315360000\"><testcase name=\"absurd\" time=\"315360000\"/></testsuite></testsuites>",
+        );
+
+        let options = ReportOptions {
+            max_time: Some(Duration::seconds(-1)),
+            ..Default::default()
+        };
+        let mut out: Vec<u8> = Vec::new();
+        r.write_xml_with(&mut out, &options).unwrap();
+
+        // language=xml
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "<?xml version=\"1.0\" encoding=\"utf-8\"?><testsuites><testsuite id=\"0\" name=\"ts1\" package=\"testsuite/ts1\" tests=\"1\" errors=\"0\" failures=\"0\" skipped=\"0\" hostname=\"localhost\" timestamp=\"1970-01-01T00:00:00Z\" time=\"0\"><testcase name=\"absurd\" time=\"0\"/></testsuite></testsuites>",
+        );
+    }
+
+    #[test]
+    fn retain_problems() {
+        let ts1 = TestSuiteBuilder::new("ts1")
+            .add_testcase(TestCase::success("good test", Duration::seconds(1)))
+            .add_testcase(TestCase::error(
+                "error test",
+                Duration::seconds(2),
+                "git error",
+                "unable to fetch",
+            ))
+            .add_testcase(TestCase::skipped("skipped test"))
+            .add_testcase(TestCase::failure(
+                "failure test",
+                Duration::seconds(3),
+                "assert_eq",
+                "not equal",
+            ))
+            .build();
+        let ts2 = TestSuiteBuilder::new("ts2")
+            .add_testcase(TestCase::success("good test", Duration::seconds(1)))
+            .build();
+        let r = ReportBuilder::new().add_testsuites([ts1, ts2]).build();
+
+        let mut kept = r.clone();
+        kept.retain_problems(false);
+        assert_eq!(kept.testsuites().len(), 2);
+        assert_eq!(kept.testsuites()[1].tests(), 0);
+
+        let mut problems = r;
+        problems.retain_problems(true);
+        assert_eq!(problems.testsuites().len(), 1);
+        let names: Vec<_> = problems.testsuites()[0]
+            .testcases
+            .iter()
+            .map(|tc| tc.name.as_str())
+            .collect();
+        assert_eq!(names, ["error test", "failure test"]);
+        assert_eq!(
+            problems.summary(),
+            Summary {
+                tests: 2,
+                errors: 1,
+                failures: 1,
+                skipped: 0,
+                disabled: 0,
+                time: Duration::seconds(5),
+                assertions: None,
+            }
+        );
+    }
+
+    #[test]
+    fn parse_root_extra_attributes() {
+        // language=xml
+        let xml = r#"<?xml version="1.0" encoding="utf-8"?><testsuites package="org.example" tests="1" name="all"><testsuite name="ts1" tests="0"/></testsuites>"#;
+
+        let r = Report::from_reader(xml.as_bytes()).unwrap();
+        assert_eq!(r.testsuites().len(), 1);
+        assert_eq!(r.extra_attributes().len(), 1);
+        assert_eq!(r.extra_attributes()["package"], "org.example");
+        assert_eq!(r.name().as_deref(), Some("all"));
+
+        let mut out: Vec<u8> = Vec::new();
+        r.write_xml(&mut out).unwrap();
+        assert!(String::from_utf8(out).unwrap().contains(
+            r#"<testsuites name="all" tests="0" failures="0" errors="0" time="0" package="org.example">"#
+        ));
+    }
+
+    #[test]
+    fn crlf_line_ending() {
+        let tc = TestCaseBuilder::failure("failure test", Duration::ZERO, "assert_eq", "not equal")
+            .set_trace("left: 1\nright: 2\r\n")
+            .set_system_out("first\nsecond\n")
+            .build();
+        let ts = TestSuiteBuilder::new("ts1")
+            .set_timestamp(datetime!(1970-01-01 00:00 UTC))
+            .add_testcase(tc)
+            .build();
+        let r = ReportBuilder::new().add_testsuite(ts).build();
+
+        let options = ReportOptions {
+            line_ending: LineEnding::CrLf,
+            ..Default::default()
+        };
+        let mut out: Vec<u8> = Vec::new();
+        r.write_xml_with(&mut out, &options).unwrap();
+        let out = String::from_utf8(out).unwrap();
+
+        assert_eq!(out.matches('\n').count(), 4);
+        assert_eq!(out.matches("\r\n").count(), 4);
+        assert!(out.contains("<![CDATA[left: 1\r\nright: 2\r\n]]>"));
+        assert!(out.contains("<![CDATA[first\r\nsecond\r\n]]>"));
+    }
+
+    #[test]
+    fn testcases_with_body() {
+        let ts = TestSuiteBuilder::new("ts1")
+            .set_timestamp(datetime!(1970-01-01 00:00 UTC))
+            .add_testcase(TestCase::failure_with_body(
+                "failure test",
+                Duration::seconds(1),
+                "assert_eq",
+                "not equal",
+                "at foo.rs:12",
+            ))
+            .add_testcase(TestCase::error_with_body(
+                "error test",
+                Duration::seconds(2),
+                "git error",
+                "unable to fetch",
+                "at bar.rs:3",
+            ))
+            .build();
+        let r = ReportBuilder::new().add_testsuite(ts).build();
+
+        let mut out: Vec<u8> = Vec::new();
+        r.write_xml(&mut out).unwrap();
+
+        // language=xml
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "<?xml version=\"1.0\" encoding=\"utf-8\"?><testsuites><testsuite id=\"0\" name=\"ts1\" package=\"testsuite/ts1\" tests=\"2\" errors=\"1\" failures=\"1\" skipped=\"0\" hostname=\"localhost\" timestamp=\"1970-01-01T00:00:00Z\" time=\"3\"><testcase name=\"failure test\" time=\"1\"><failure type=\"assert_eq\" message=\"not equal\"><![CDATA[at foo.rs:12]]></failure></testcase><testcase name=\"error test\" time=\"2\"><error type=\"git error\" message=\"unable to fetch\"><![CDATA[at bar.rs:3]]></error></testcase></testsuite></testsuites>",
+        );
+    }
+
+    #[test]
+    fn stats_by_suite() {
+        let ts1 = TestSuiteBuilder::new("ts1")
+            .add_testcase(TestCase::success("good test", Duration::seconds(1)))
+            .add_testcase(TestCase::skipped("skipped test"))
+            .build();
+        let ts2 = TestSuiteBuilder::new("ts2")
+            .add_testcase(TestCase::error(
+                "error test",
+                Duration::seconds(2),
+                "git error",
+                "unable to fetch",
+            ))
+            .add_testcase(TestCase::failure(
+                "failure test",
+                Duration::seconds(3),
+                "assert_eq",
+                "not equal",
+            ))
+            .build();
+        let r = ReportBuilder::new()
+            .add_testsuites([ts1.clone(), ts2.clone()])
+            .build();
+
+        let stats = r.stats_by_suite();
+        assert_eq!(
+            stats,
+            [
+                ("ts1".to_string(), ts1.summary()),
+                ("ts2".to_string(), ts2.summary())
+            ]
+        );
+        assert_eq!(stats[0].1.tests, ts1.tests());
+        assert_eq!(stats[0].1.skipped, ts1.skipped());
+        assert_eq!(stats[1].1.errors, ts2.errors());
+        assert_eq!(stats[1].1.failures, ts2.failures());
+        assert_eq!(stats[1].1.time, ts2.time());
+    }
+
+    #[test]
+    fn ant_preset() {
+        let ts = TestSuiteBuilder::new("FooTest")
+            .set_timestamp(datetime!(2024-03-01 10:00:00.5 UTC))
+            .set_package("com.example")
+            .add_testcase(
+                TestCaseBuilder::success("testOk", Duration::milliseconds(1))
+                    .set_classname("com.example.FooTest")
+                    .set_filepath("FooTest.java")
+                    .set_system_out("ignored")
+                    .build(),
+            )
+            .add_testcase(
+                TestCaseBuilder::success("testFail", Duration::milliseconds(2))
+                    .add_failure_at("AssertionError", "expected 1", "FooTest.java", 12)
+                    .build(),
+            )
+            .add_system_out("first ")
+            .add_system_out("second")
+            .build();
+        let r = ReportBuilder::new().add_testsuite(ts).build();
+
+        let mut out: Vec<u8> = Vec::new();
+        r.write_xml_with(&mut out, &ReportOptions::ant()).unwrap();
+
+        // The timestamp without UTC offset is only read with lenient timestamps
+        assert!(matches!(
+            Report::from_reader(out.as_slice()),
+            Err(Error::Parse(_))
+        ));
+        let lenient = ParseOptions {
+            lenient_timestamps: true,
+            ..Default::default()
+        };
+        let parsed = Report::from_reader_with(out.as_slice(), &lenient).unwrap();
+        assert_eq!(
+            parsed.testsuites()[0].timestamp,
+            Some(datetime!(2024-03-01 10:00 UTC))
+        );
+
+        // language=xml
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "<?xml version=\"1.0\" encoding=\"utf-8\"?><testsuites><testsuite id=\"0\" name=\"FooTest\" package=\"com.example\" tests=\"2\" errors=\"0\" failures=\"1\" hostname=\"localhost\" timestamp=\"2024-03-01T10:00:00\" time=\"0.003\"><properties/><testcase name=\"testOk\" classname=\"com.example.FooTest\" time=\"0.001\"/><testcase name=\"testFail\" classname=\"\" time=\"0.002\"><failure type=\"AssertionError\" message=\"expected 1\"/></testcase><system-out><![CDATA[first second]]></system-out><system-err><![CDATA[]]></system-err></testsuite></testsuites>",
+        );
+    }
+
+    #[test]
+    fn time_unit() {
+        let ts = TestSuiteBuilder::new("ts1")
+            .set_timestamp(datetime!(1970-01-01 00:00 UTC))
+            .add_testcase(TestCase::success("good test", Duration::milliseconds(1500)))
+            .build();
+        let r = ReportBuilder::new().add_testsuite(ts).build();
+
+        let mut seconds: Vec<u8> = Vec::new();
+        r.write_xml(&mut seconds).unwrap();
+        assert!(String::from_utf8(seconds.clone())
+            .unwrap()
+            .contains(r#"<testcase name="good test" time="1.5"/>"#));
+
+        let options = ReportOptions {
+            time_unit: TimeUnit::Milliseconds,
+            ..Default::default()
+        };
+        let mut millis: Vec<u8> = Vec::new();
+        r.write_xml_with(&mut millis, &options).unwrap();
+        assert!(String::from_utf8(millis.clone())
+            .unwrap()
+            .contains(r#"<testcase name="good test" time="1500"/>"#));
+
+        let parsed = Report::from_reader(seconds.as_slice()).unwrap();
+        assert_eq!(
+            parsed.testsuites()[0].testcases[0].time,
+            Duration::milliseconds(1500)
+        );
+
+        let parsed =
+            Report::from_reader_with_time_unit(millis.as_slice(), TimeUnit::Milliseconds).unwrap();
+        assert_eq!(
+            parsed.testsuites()[0].testcases[0].time,
+            Duration::milliseconds(1500)
+        );
+    }
+
+    #[test]
+    fn testcase_by_name() {
+        let mut ts = TestSuiteBuilder::new("ts1")
+            .add_testcase(TestCase::success("first", Duration::seconds(1)))
+            .add_testcase(TestCase::failure(
+                "second",
+                Duration::seconds(2),
+                "assert",
+                "nope",
+            ))
+            .add_testcase(TestCase::success("second", Duration::seconds(3)))
+            .build();
+
+        let tc = ts.testcase_by_name("second").unwrap();
+        assert!(tc.is_failure());
+        assert_eq!(tc.time, Duration::seconds(2));
+        assert!(ts.testcase_by_name("third").is_none());
+
+        ts.testcase_by_name_mut("first")
+            .unwrap()
+            .append_system_out("late output");
+        assert_eq!(ts.testcases[0].system_out, ["late output"]);
+    }
+
+    #[test]
+    fn suite_counts_add_up() {
+        let ts = TestSuiteBuilder::new("ts1")
+            .set_timestamp(datetime!(1970-01-01 00:00 UTC))
+            .add_testcase(TestCase::success("good test", Duration::ZERO))
+            .add_testcase(TestCase::success("good test 2", Duration::ZERO))
+            .add_testcase(TestCase::error(
+                "error test",
+                Duration::ZERO,
+                "git error",
+                "unable to fetch",
+            ))
+            .add_testcase(TestCase::failure(
+                "failure test",
+                Duration::ZERO,
+                "assert_eq",
+                "not equal",
+            ))
+            .add_testcase(TestCase::skipped("skipped test"))
+            .add_testcase(TestCase::skipped("skipped test 2"))
+            .build();
+
+        let passed = ts.testcases.iter().filter(|tc| tc.is_success()).count();
+        assert_eq!(
+            ts.tests(),
+            passed + ts.errors() + ts.failures() + ts.skipped()
+        );
+
+        let r = ReportBuilder::new().add_testsuite(ts).build();
+        let mut out: Vec<u8> = Vec::new();
+        r.write_xml(&mut out).unwrap();
+        assert!(String::from_utf8(out)
+            .unwrap()
+            .contains(r#"tests="6" errors="1" failures="1" skipped="2""#));
+    }
+
+    #[test]
+    fn cdata_end_in_output() {
+        let tc = TestCaseBuilder::failure("failure test", Duration::ZERO, "assert_eq", "not equal")
+            .set_trace("left: ]]>")
+            .set_system_out("a]]>b")
+            .build();
+        let r = ReportBuilder::new()
+            .add_testsuite(
+                TestSuiteBuilder::new("ts1")
+                    .set_timestamp(datetime!(1970-01-01 00:00 UTC))
+                    .add_testcase(tc)
+                    .build(),
+            )
+            .build();
+
+        let mut out: Vec<u8> = Vec::new();
+        r.write_xml(&mut out).unwrap();
+
+        // language=xml
+        assert_eq!(
+            String::from_utf8(out.clone()).unwrap(),
+            "\
+<?xml version=\"1.0\" encoding=\"utf-8\"?>\
+<testsuites>\
+  <testsuite id=\"0\" name=\"ts1\" package=\"testsuite/ts1\" tests=\"1\" errors=\"0\" failures=\"1\" skipped=\"0\" hostname=\"localhost\" timestamp=\"1970-01-01T00:00:00Z\" time=\"0\">\
+    <testcase name=\"failure test\" time=\"0\">\
+      <failure type=\"assert_eq\" message=\"not equal\"><![CDATA[left: ]]]]><![CDATA[>]]></failure>\
+      <system-out><![CDATA[a]]]]><![CDATA[>b]]></system-out>\
+    </testcase>\
+  </testsuite>\
+</testsuites>",
+        );
+        assert_eq!(Report::from_reader(out.as_slice()).unwrap(), r);
+    }
+
+    #[test]
+    fn display_test_result() {
+        let result = |tc: TestCase| tc.result().to_string();
+
+        assert_eq!(
+            result(TestCase::success("good test", Duration::ZERO)),
+            "success"
+        );
+        assert_eq!(result(TestCase::skipped("skipped test")), "skipped");
+        assert_eq!(
+            result(TestCase::error(
+                "error test",
+                Duration::ZERO,
+                "git error",
+                "unable to fetch"
+            )),
+            "error: git error: unable to fetch"
+        );
+        assert_eq!(
+            result(
+                TestCaseBuilder::failure(
+                    "failure test",
+                    Duration::ZERO,
+                    "assert_eq",
+                    "not equal\nleft: 1\r\nright: 2"
+                )
+                .set_trace("trace")
+                .build()
+            ),
+            "failure: assert_eq: not equal left: 1 right: 2"
+        );
+    }
+
+    #[test]
+    fn write_xml_with_format_options() {
+        let ts = TestSuiteBuilder::new("ts1")
+            .set_timestamp(datetime!(1970-01-01 00:00 UTC))
+            .add_testcase(
+                TestCaseBuilder::failure("failure test", Duration::ZERO, "assert_eq", "not equal")
+                    .set_trace("left < right")
+                    .build(),
+            )
+            .add_testcase(TestCase::skipped("skipped test"))
+            .build();
+        let r = ReportBuilder::new().add_testsuite(ts).build();
+
+        let options = ReportOptions {
+            indent: Some((b' ', 2)),
+            text_mode: TextMode::Escaped,
+            ..Default::default()
+        };
+        let mut out: Vec<u8> = Vec::new();
+        r.write_xml_with(&mut out, &options).unwrap();
+
+        // language=xml
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            r#"<?xml version="1.0" encoding="utf-8"?>
+<testsuites>
+  <testsuite id="0" name="ts1" package="testsuite/ts1" tests="2" errors="0" failures="1" skipped="1" hostname="localhost" timestamp="1970-01-01T00:00:00Z" time="0">
+    <testcase name="failure test" time="0">
+      <failure type="assert_eq" message="not equal">left &lt; right</failure>
+    </testcase>
+    <testcase name="skipped test" time="0">
+      <skipped/>
+    </testcase>
+  </testsuite>
+</testsuites>"#,
+        );
+
+        let options = ReportOptions {
+            omit_declaration: true,
+            expand_empty_elements: true,
+            ..Default::default()
+        };
+        let mut out: Vec<u8> = Vec::new();
+        r.write_xml_with(&mut out, &options).unwrap();
+
+        // language=xml
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "\
+<testsuites>\
+  <testsuite id=\"0\" name=\"ts1\" package=\"testsuite/ts1\" tests=\"2\" errors=\"0\" failures=\"1\" skipped=\"1\" hostname=\"localhost\" timestamp=\"1970-01-01T00:00:00Z\" time=\"0\">\
+    <testcase name=\"failure test\" time=\"0\">\
+      <failure type=\"assert_eq\" message=\"not equal\"><![CDATA[left < right]]></failure>\
+    </testcase>\
+    <testcase name=\"skipped test\" time=\"0\">\
+      <skipped></skipped>\
+    </testcase>\
+  </testsuite>\
+</testsuites>",
+        );
+    }
+
+    #[test]
+    fn disabled_testcase() {
+        let ts = TestSuiteBuilder::new("ts1")
+            .set_timestamp(datetime!(1970-01-01 00:00 UTC))
+            .add_testcase(TestCase::skipped("skipped test"))
+            .add_testcase(TestCase::disabled("disabled test"))
+            .build();
+        assert_eq!(ts.tests(), 2);
+        assert_eq!(ts.skipped(), 1);
+        assert_eq!(ts.disabled(), 1);
+        assert_eq!(ts.summary().disabled, 1);
+
+        let r = ReportBuilder::new().add_testsuite(ts).build();
+        let mut out: Vec<u8> = Vec::new();
+        r.write_xml(&mut out).unwrap();
+
+        // language=xml
+        assert_eq!(
+            String::from_utf8(out.clone()).unwrap(),
+            "\
+<?xml version=\"1.0\" encoding=\"utf-8\"?>\
+<testsuites>\
+  <testsuite id=\"0\" name=\"ts1\" package=\"testsuite/ts1\" tests=\"2\" errors=\"0\" failures=\"0\" skipped=\"1\" disabled=\"1\" hostname=\"localhost\" timestamp=\"1970-01-01T00:00:00Z\" time=\"0\">\
+    <testcase name=\"skipped test\" time=\"0\">\
+      <skipped/>\
+    </testcase>\
+    <testcase name=\"disabled test\" time=\"0\">\
+      <skipped type=\"disabled\"/>\
+    </testcase>\
+  </testsuite>\
+</testsuites>",
+        );
+        assert_eq!(r.validate_against_xsd(), Ok(()));
+
+        let parsed = Report::from_reader(out.as_slice()).unwrap();
+        assert!(parsed.testsuites()[0].testcases[1].is_disabled());
+        assert_eq!(parsed, r);
+    }
+
+    #[test]
+    fn testcase_from_result_and_panic() {
+        let ok = TestCase::from_result::<std::fmt::Error>("ok", Duration::ZERO, Ok(()));
+        assert!(ok.is_success());
+
+        let err = TestCase::from_result("err", Duration::ZERO, Err("not equal"));
+        assert_eq!(err.failure_type(), Some("&str"));
+        assert_eq!(err.failure_message(), Some("not equal"));
+
+        let payload = std::panic::catch_unwind(|| panic!("left != {}", "right")).unwrap_err();
+        let panicked = TestCase::from_panic("panic", Duration::ZERO, payload.as_ref());
+        assert_eq!(panicked.failure_type(), Some("panic"));
+        assert_eq!(panicked.failure_message(), Some("left != right"));
+
+        let payload = std::panic::catch_unwind(|| std::panic::panic_any(42)).unwrap_err();
+        let panicked = TestCase::from_panic("panic", Duration::ZERO, payload.as_ref());
+        assert_eq!(panicked.failure_message(), Some("Box<dyn Any>"));
+    }
+
+    #[test]
+    fn suite_assertions() {
+        let mut tc1 = TestCase::success("good test", Duration::ZERO);
+        tc1.set_assertions(3);
+        let mut tc2 = TestCase::failure("failure test", Duration::ZERO, "assert_eq", "not equal");
+        tc2.set_assertions(2);
+        let tc3 = TestCase::success("good test 2", Duration::ZERO);
+
+        let without = TestSuiteBuilder::new("without")
+            .add_testcase(tc3.clone())
+            .build();
+        assert_eq!(without.assertions(), None);
+
+        let ts = TestSuiteBuilder::new("ts1")
+            .set_timestamp(datetime!(1970-01-01 00:00 UTC))
+            .add_testcases([tc1, tc2, tc3])
+            .build();
+        assert_eq!(ts.assertions(), Some(5));
+        assert_eq!(ts.summary().assertions, Some(5));
+
+        let r = ReportBuilder::new()
+            .add_testsuite(ts)
+            .add_testsuite(without)
+            .build();
+        assert_eq!(r.summary().assertions, Some(5));
+
+        let mut out: Vec<u8> = Vec::new();
+        r.write_xml(&mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.contains(r#"failures="1" skipped="0" assertions="5" hostname="localhost""#));
+        assert_eq!(out.matches("assertions=").count(), 3);
+    }
+
+    #[test]
+    fn suite_assertions_saturate() {
+        let mut tc = TestCase::success("good test", Duration::ZERO);
+        tc.set_assertions(u64::MAX);
+        let ts = TestSuiteBuilder::new("ts1")
+            .add_testcases([tc.clone(), tc])
+            .build();
+        assert_eq!(ts.assertions(), Some(u64::MAX));
+        assert_eq!(ts.summary().assertions, Some(u64::MAX));
+
+        let r = ReportBuilder::new()
+            .add_testsuite(ts.clone())
+            .add_testsuite(ts)
+            .build();
+        assert_eq!(r.summary().assertions, Some(u64::MAX));
+    }
+
+    #[cfg(feature = "junit5")]
+    #[test]
+    fn open_test_reporting_events() {
+        let ts = TestSuiteBuilder::new("ts1")
+            .set_timestamp(datetime!(1970-01-01 00:00 UTC))
+            .add_testcase(TestCase::success("good test", Duration::seconds(1)))
+            .add_testcase(TestCase::failure(
+                "failure test",
+                Duration::seconds(2),
+                "assert_eq",
+                "left < right",
+            ))
+            .add_testcase(TestCase::skipped("skipped test"))
+            .build();
+        let r = ReportBuilder::new().add_testsuite(ts).build();
+
+        let mut out: Vec<u8> = Vec::new();
+        r.write_open_test_reporting(&mut out).unwrap();
+
+        // language=xml
+        assert_eq!(
+            String::from_utf8(out.clone()).unwrap(),
+            "\
+<?xml version=\"1.0\" encoding=\"UTF-8\"?>\
+<e:events xmlns=\"https://schemas.opentest4j.org/reporting/core/0.2.0\" xmlns:e=\"https://schemas.opentest4j.org/reporting/events/0.2.0\">\
+  <e:started id=\"1\" name=\"ts1\" time=\"1970-01-01T00:00:00Z\"/>\
+  <e:started id=\"2\" parentId=\"1\" name=\"good test\" time=\"1970-01-01T00:00:00Z\"/>\
+  <e:finished id=\"2\" time=\"1970-01-01T00:00:01Z\"><result status=\"SUCCESSFUL\"/></e:finished>\
+  <e:started id=\"3\" parentId=\"1\" name=\"failure test\" time=\"1970-01-01T00:00:01Z\"/>\
+  <e:finished id=\"3\" time=\"1970-01-01T00:00:03Z\">\
+    <result status=\"FAILED\"><reason>left &lt; right</reason></result>\
+  </e:finished>\
+  <e:reported id=\"4\" parentId=\"1\" name=\"skipped test\" time=\"1970-01-01T00:00:03Z\">\
+    <result status=\"SKIPPED\"/>\
+  </e:reported>\
+  <e:finished id=\"1\" time=\"1970-01-01T00:00:03Z\"><result status=\"SUCCESSFUL\"/></e:finished>\
+</e:events>",
+        );
+
+        let mut reader = quick_xml::Reader::from_reader(out.as_slice());
+        let mut buf = Vec::new();
+        while reader.read_event_into(&mut buf).unwrap() != quick_xml::events::Event::Eof {}
+    }
+
+    #[test]
+    fn lenient_timestamps() {
+        let xml = |timestamp: &str| {
+            format!(
+                r#"<testsuite name="ts1" tests="0" timestamp="{}"/>"#,
+                timestamp
+            )
+        };
+        let lenient = ParseOptions {
+            lenient_timestamps: true,
+            ..Default::default()
+        };
+
+        for (timestamp, expected) in [
+            ("2024-01-01 10:00:00", datetime!(2024-01-01 10:00 UTC)),
+            ("2024-01-01T10:00:00", datetime!(2024-01-01 10:00 UTC)),
+            (
+                "2024-03-01T10:00:00.123456",
+                datetime!(2024-03-01 10:00:00.123456 UTC),
+            ),
+            ("2024-01-01 10:00:00+02:00", datetime!(2024-01-01 10:00 +2)),
+        ] {
+            assert!(matches!(
+                Report::from_reader(xml(timestamp).as_bytes()),
+                Err(Error::Parse(_))
+            ));
+            let r = Report::from_reader_with(xml(timestamp).as_bytes(), &lenient).unwrap();
+            assert_eq!(r.testsuites()[0].timestamp, Some(expected));
+        }
+
+        assert!(Report::from_reader_with(xml("yesterday").as_bytes(), &lenient).is_err());
+    }
+
+    #[test]
+    fn estimated_xml_size() {
+        let mut r = Report::new();
+        let mut previous = r.estimated_xml_size();
+        let mut check = |r: &Report| {
+            let mut out: Vec<u8> = Vec::new();
+            r.write_xml(&mut out).unwrap();
+            let estimate = r.estimated_xml_size();
+            assert!(estimate * 2 >= out.len() && estimate <= out.len() * 2);
+            assert!(estimate >= previous);
+            previous = estimate;
+        };
+        check(&r);
+
+        let mut ts = TestSuite::new("ts1");
+        ts.add_property("os", "linux");
+        ts.set_system_out("suite output");
+        r.add_testsuite(ts.clone());
+        check(&r);
+
+        ts.add_testcases([
+            TestCaseBuilder::success("good test", Duration::milliseconds(15))
+                .set_classname("MyClass")
+                .set_filepath("./foo.rs")
+                .build(),
+            TestCase::error(
+                "error test",
+                Duration::seconds(5),
+                "git error",
+                "unable to fetch",
+            ),
+            TestCaseBuilder::failure("failure test", Duration::ZERO, "assert_eq", "not equal")
+                .set_trace("left: 1\nright: 2")
+                .set_system_out("some output")
+                .build(),
+            TestCase::skipped("skipped test"),
+        ]);
+        r.add_testsuite(ts);
+        check(&r);
+
+        r.set_system_err(&"x".repeat(10_000));
+        check(&r);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn testcase_metadata() {
+        let tc = TestCaseBuilder::success("good test", Duration::seconds(1))
+            .add_metadata("runner", "ci-3")
+            .build();
+
+        let json = serde_json::to_value(&tc).unwrap();
+        assert_eq!(json["metadata"]["runner"], "ci-3");
+        let deserialized: TestCase = serde_json::from_value(json).unwrap();
+        assert_eq!(deserialized, tc);
+
+        let r = ReportBuilder::new()
+            .add_testsuite(TestSuiteBuilder::new("ts1").add_testcase(tc).build())
+            .build();
+        let mut out: Vec<u8> = Vec::new();
+        r.write_xml(&mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert!(!out.contains("runner"));
+        assert!(!out.contains("ci-3"));
+    }
+
+    #[test]
+    fn testcase_hostname() {
+        let mut ts = TestSuiteBuilder::new("ts1")
+            .set_timestamp(datetime!(1970-01-01 00:00 UTC))
+            .add_testcase(
+                TestCaseBuilder::success("remote test", Duration::ZERO)
+                    .set_hostname("worker-1")
+                    .build(),
+            )
+            .add_testcase(TestCase::success("local test", Duration::ZERO))
+            .build();
+        ts.hostname = "controller".into();
+        let r = ReportBuilder::new().add_testsuite(ts).build();
+
+        let mut out: Vec<u8> = Vec::new();
+        r.write_xml(&mut out).unwrap();
+
+        // language=xml
+        assert_eq!(
+            String::from_utf8(out.clone()).unwrap(),
+            "\
+<?xml version=\"1.0\" encoding=\"utf-8\"?>\
+<testsuites>\
+  <testsuite id=\"0\" name=\"ts1\" package=\"testsuite/ts1\" tests=\"2\" errors=\"0\" failures=\"0\" skipped=\"0\" hostname=\"controller\" timestamp=\"1970-01-01T00:00:00Z\" time=\"0\">\
+    <testcase name=\"remote test\" time=\"0\" hostname=\"worker-1\"/>\
+    <testcase name=\"local test\" time=\"0\"/>\
+  </testsuite>\
+</testsuites>",
+        );
+        assert_eq!(r.validate_against_xsd(), Ok(()));
+        assert_eq!(Report::from_reader(out.as_slice()).unwrap(), r);
+    }
+
+    #[test]
+    fn sort_testcases_by() {
+        let mut r = ReportBuilder::new()
+            .add_testsuite(
+                TestSuiteBuilder::new("ts1")
+                    .add_testcase(TestCase::success("b", Duration::seconds(1)))
+                    .add_testcase(TestCase::success("a", Duration::seconds(2)))
+                    .add_testcase(TestCase::skipped("c"))
+                    .build(),
+            )
+            .add_testsuite(
+                TestSuiteBuilder::new("ts2")
+                    .add_testcase(TestCase::success("z", Duration::seconds(1)))
+                    .add_testcase(TestCase::success("x", Duration::seconds(1)))
+                    .add_testcase(TestCase::success("y", Duration::seconds(3)))
+                    .build(),
+            )
+            .build();
+        let names = |r: &Report| -> Vec<Vec<String>> {
+            r.testsuites()
+                .iter()
+                .map(|ts| ts.testcases.iter().map(|tc| tc.name.clone()).collect())
+                .collect()
+        };
+
+        r.sort_testcases_by(|tc| tc.name.clone());
+        assert_eq!(names(&r), [["a", "b", "c"], ["x", "y", "z"]]);
+
+        // Stable, "x" and "z" keep their order
+        r.sort_testcases_by(|tc| tc.time);
+        assert_eq!(names(&r), [["c", "b", "a"], ["x", "z", "y"]]);
+    }
+
+    #[test]
+    fn testcase_time_omission() {
+        let ts = TestSuiteBuilder::new("ts1")
+            .set_timestamp(datetime!(1970-01-01 00:00 UTC))
+            .add_testcase(TestCase::success("good test", Duration::ZERO))
+            .add_testcase(TestCase::skipped("skipped test"))
+            .build();
+        let r = ReportBuilder::new().add_testsuite(ts).build();
+        let write = |testcase_time| {
+            let mut out: Vec<u8> = Vec::new();
+            let options = ReportOptions {
+                testcase_time,
+                ..Default::default()
+            };
+            r.write_xml_with(&mut out, &options).unwrap();
+            let out = String::from_utf8(out).unwrap();
+            let start = out.find("<testcase").unwrap();
+            out[start..out.find("</testsuite>").unwrap()].to_owned()
+        };
+
+        assert_eq!(
+            write(TestCaseTime::Always),
+            r#"<testcase name="good test" time="0"/><testcase name="skipped test" time="0"><skipped/></testcase>"#
+        );
+        assert_eq!(
+            write(TestCaseTime::OmitZeroSkipped),
+            r#"<testcase name="good test" time="0"/><testcase name="skipped test"><skipped/></testcase>"#
+        );
+        assert_eq!(
+            write(TestCaseTime::Omit),
+            r#"<testcase name="good test"/><testcase name="skipped test"><skipped/></testcase>"#
+        );
+
+        let mut out: Vec<u8> = Vec::new();
+        let options = ReportOptions {
+            testcase_time: TestCaseTime::Omit,
+            ..Default::default()
+        };
+        r.write_xml_with(&mut out, &options).unwrap();
+        assert_eq!(Report::from_reader(out.as_slice()).unwrap(), r);
+    }
+
+    #[test]
+    fn testcase_status() {
+        for (tc, status) in [
+            (
+                TestCase::success("good test", Duration::ZERO),
+                Status::Passed,
+            ),
+            (
+                TestCase::failure("failure test", Duration::ZERO, "assert_eq", "not equal"),
+                Status::Failed,
+            ),
+            (
+                TestCase::error("error test", Duration::ZERO, "git error", "unable to fetch"),
+                Status::Errored,
+            ),
+            (TestCase::skipped("skipped test"), Status::Skipped),
+            (TestCase::disabled("disabled test"), Status::Disabled),
+        ] {
+            assert_eq!(tc.status(), status);
+            assert_eq!(tc.result().status(), status);
+        }
+    }
+
+    #[test]
+    fn testcase_group() {
+        let r = ReportBuilder::new()
+            .add_testsuite(
+                TestSuiteBuilder::new("ts1")
+                    .add_testcase(
+                        TestCaseBuilder::success("good test", Duration::ZERO)
+                            .set_group("smoke")
+                            .build(),
+                    )
+                    .add_testcase(TestCase::success("other test", Duration::ZERO))
+                    .build(),
+            )
+            .build();
+
+        let mut out: Vec<u8> = Vec::new();
+        r.write_xml(&mut out).unwrap();
+        assert!(String::from_utf8(out.clone())
+            .unwrap()
+            .contains(r#"<testcase name="good test" time="0" group="smoke"/><testcase name="other test" time="0"/>"#));
+        assert_eq!(r.validate_against_xsd(), Ok(()));
+
+        let parsed = Report::from_reader(out.as_slice()).unwrap();
+        assert_eq!(
+            parsed.testsuites()[0].testcases[0].group.as_deref(),
+            Some("smoke")
+        );
+        assert_eq!(parsed, r);
+    }
+
+    #[test]
+    fn getters() {
+        let tc = TestCaseBuilder::success("good test", Duration::seconds(1))
+            .set_classname("MyClass")
+            .build();
+        let tcb = TestCaseBuilder::from_testcase(tc.clone());
+        assert_eq!(tcb.testcase(), &tc);
+        assert_eq!(tc.name(), "good test");
+        assert_eq!(tc.time(), &Duration::seconds(1));
+        assert_eq!(tc.result(), &crate::TestResult::Success);
+        assert_eq!(tc.classname().as_deref(), Some("MyClass"));
+
+        let mut ts = TestSuite::new("ts1");
+        ts.add_property("key", "value");
+        ts.add_testcase(tc);
+        assert_eq!(ts.name(), "ts1");
+        assert_eq!(ts.hostname(), "localhost");
+        assert_eq!(ts.properties()[0].name(), "key");
+        assert_eq!(ts.properties()[0].value(), "value");
+        assert_eq!(ts.testcases().len(), 1);
+        assert_eq!(ts.summary().tests(), &1);
+        assert_eq!(TestSuiteBuilder::new("ts1").testsuite().name(), "ts1");
+
+        let mut rb = ReportBuilder::new();
+        rb.add_testsuite(ts);
+        assert_eq!(rb.report().testsuites().len(), 1);
+        assert!(rb.build().extra_attributes().is_empty());
+    }
+
+    #[test]
+    fn write_header_suite_footer() {
+        let ts1 = TestSuiteBuilder::new("ts1")
+            .set_timestamp(datetime!(1970-01-01 01:01 UTC))
+            .build();
+        let ts2 = TestSuiteBuilder::new("ts2")
+            .set_timestamp(datetime!(1970-01-01 01:01 UTC))
+            .add_testcase(TestCase::success("good test", Duration::seconds(1)))
+            .build();
+
+        let mut writer = Report::write_header(Vec::new()).unwrap();
+        Report::write_suite(&mut writer, &ts1, 0, &ReportOptions::default()).unwrap();
+        Report::write_suite(&mut writer, &ts2, 1, &ReportOptions::default()).unwrap();
+        let out = Report::write_footer(writer).unwrap();
+
+        let r = ReportBuilder::new().add_testsuites(vec![ts1, ts2]).build();
+        let mut expected: Vec<u8> = Vec::new();
+        r.write_xml(&mut expected).unwrap();
+        assert_eq!(
+            String::from_utf8(out.clone()).unwrap(),
+            String::from_utf8(expected).unwrap()
+        );
+        assert_eq!(Report::from_reader(out.as_slice()).unwrap(), r);
+    }
+
+    #[test]
+    fn write_suite_with_options() {
+        let ts = TestSuiteBuilder::new("ts1")
+            .set_timestamp(datetime!(1970-01-01 01:01 UTC))
+            .set_system_out("line\u{1b}1\nline 2")
+            .add_testcase(TestCase::failure(
+                "bad test",
+                Duration::seconds(-1),
+                "assertion",
+                "a\u{ffff}b",
+            ))
+            .build();
+        let options = ReportOptions {
+            line_ending: LineEnding::CrLf,
+            strict: true,
+            ..ReportOptions::pytest()
+        };
+
+        let mut writer = Report::write_header(Vec::new()).unwrap();
+        Report::write_suite(&mut writer, &ts, 0, &options).unwrap();
+        let out = Report::write_footer(writer).unwrap();
+
+        let r = ReportBuilder::new().add_testsuite(ts).build();
+        let mut expected: Vec<u8> = Vec::new();
+        r.write_xml_with(&mut expected, &options).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            String::from_utf8(expected).unwrap()
+        );
+
+        let mut writer = Report::write_header(Vec::new()).unwrap();
+        let unnamed = TestSuiteBuilder::new("").build();
+        assert!(matches!(
+            Report::write_suite(&mut writer, &unnamed, 0, &options),
+            Err(Error::Validation(_))
+        ));
+    }
+
+    #[test]
+    fn testcase_properties() {
+        let r = ReportBuilder::new()
+            .add_testsuite(
+                TestSuiteBuilder::new("ts1")
+                    .set_timestamp(datetime!(1970-01-01 01:01 UTC))
+                    .add_testcase(
+                        TestCaseBuilder::success("good test", Duration::ZERO)
+                            .add_property("input", "1")
+                            .add_property("expected", "2")
+                            .build(),
+                    )
+                    .add_testcase(TestCase::success("other test", Duration::ZERO))
+                    .build(),
+            )
+            .build();
+
+        let mut out: Vec<u8> = Vec::new();
+        r.write_xml(&mut out).unwrap();
+        // language=xml
+        let expected = "\
+<?xml version=\"1.0\" encoding=\"utf-8\"?>\
+<testsuites>\
+<testsuite id=\"0\" name=\"ts1\" package=\"testsuite/ts1\" tests=\"2\" errors=\"0\" failures=\"0\" skipped=\"0\" hostname=\"localhost\" timestamp=\"1970-01-01T01:01:00Z\" time=\"0\">\
+<testcase name=\"good test\" time=\"0\">\
+<properties>\
+<property name=\"input\" value=\"1\"/>\
+<property name=\"expected\" value=\"2\"/>\
+</properties>\
+</testcase>\
+<testcase name=\"other test\" time=\"0\"/>\
+</testsuite>\
+</testsuites>";
+        assert_eq!(String::from_utf8(out.clone()).unwrap(), expected);
+        assert_eq!(r.validate_against_xsd(), Ok(()));
+
+        let parsed = Report::from_reader(out.as_slice()).unwrap();
+        assert_eq!(parsed.testsuites()[0].testcases[0].properties().len(), 2);
+        assert_eq!(parsed, r);
+    }
+
+    #[test]
+    fn map_durations() {
+        let mut ts1 = TestSuiteBuilder::new("ts1")
+            .add_testcase(TestCase::success("good test", Duration::seconds(15)))
+            .add_testcase(TestCase::failure(
+                "failure test",
+                Duration::milliseconds(10),
+                "assert_eq",
+                "not equal",
+            ))
+            .build();
+        ts1.add_time(Duration::seconds(1));
+        let mut ts2 = TestSuite::new("ts2");
+        ts2.set_time(Duration::seconds(3));
+        let mut r = ReportBuilder::new().add_testsuites(vec![ts1, ts2]).build();
+
+        r.map_durations(|_| Duration::ZERO);
+
+        let mut out: Vec<u8> = Vec::new();
+        let options = ReportOptions {
+            root_totals: true,
+            ..ReportOptions::default()
+        };
+        r.write_xml_with(&mut out, &options).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert_eq!(out.matches(" time=\"0\"").count(), 5);
+        assert_eq!(out.matches(" time=").count(), 5);
+    }
+
+    #[test]
+    fn testsuite_set_name() {
+        let mut ts = TestSuite::new("ts1");
+        ts.set_name("ts2", false);
+        assert_eq!(ts.name(), "ts2");
+        assert_eq!(ts.package(), "testsuite/ts1");
+
+        ts.set_name("ts3", true);
+        assert_eq!(ts.name(), "ts3");
+        assert_eq!(ts.package(), "testsuite/ts3");
+
+        let ts = TestSuiteBuilder::new("ts1")
+            .set_package("com.example")
+            .set_name("ts2", false)
+            .build();
+        assert_eq!(ts.name(), "ts2");
+        assert_eq!(ts.package(), "com.example");
+    }
+
+    #[test]
+    fn testcase_error_from() {
+        #[derive(Debug)]
+        enum MyError {
+            Io(std::io::Error),
+        }
+
+        impl std::fmt::Display for MyError {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.write_str("cannot read fixture")
+            }
+        }
+
+        impl std::error::Error for MyError {
+            fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+                match self {
+                    MyError::Io(e) => Some(e),
+                }
+            }
+        }
+
+        let e = MyError::Io(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            "file not found",
+        ));
+        let tc = TestCase::error_from("error test", Duration::seconds(1), &e);
+        assert!(tc.is_error());
+        assert_eq!(
+            tc.result(),
+            &crate::TestResult::Error {
+                type_: std::any::type_name::<MyError>().into(),
+                message: "cannot read fixture".into(),
+                cause: Some("file not found".into()),
+                file: None,
+                line: None,
+            }
+        );
+    }
+
+    #[test]
+    fn write_fragment() {
+        let r = ReportBuilder::new()
+            .add_testsuite(
+                TestSuiteBuilder::new("ts1")
+                    .set_timestamp(datetime!(1970-01-01 01:01 UTC))
+                    .add_testcase(TestCase::success("good test", Duration::ZERO))
+                    .build(),
+            )
+            .build();
+
+        let mut out: Vec<u8> = Vec::new();
+        r.write_fragment(&mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.starts_with("<testsuites"));
+        assert!(out.ends_with("</testsuites>"));
+        assert!(!out.contains("<?xml"));
+
+        let mut full: Vec<u8> = Vec::new();
+        r.write_xml(&mut full).unwrap();
+        assert!(String::from_utf8(full).unwrap().ends_with(&out));
+    }
+
+    #[test]
+    fn exit_code() {
+        let mut r = ReportBuilder::new()
+            .add_testsuite(
+                TestSuiteBuilder::new("ts1")
+                    .add_testcase(TestCase::success("good test", Duration::ZERO))
+                    .add_testcase(TestCase::skipped("skipped test"))
+                    .build(),
+            )
+            .build();
+        assert_eq!(r.num_problems(), 0);
+        assert_eq!(r.exit_code(), 0);
+
+        r.add_testsuite(
+            TestSuiteBuilder::new("ts2")
+                .add_testcase(TestCase::failure(
+                    "failure test",
+                    Duration::ZERO,
+                    "assert_eq",
+                    "not equal",
+                ))
+                .add_testcase(TestCase::error(
+                    "error test",
+                    Duration::ZERO,
+                    "git error",
+                    "unable to fetch",
+                ))
+                .build(),
+        );
+        assert_eq!(r.num_problems(), 2);
+        assert_eq!(r.exit_code(), 1);
+    }
+
+    #[test]
+    fn testcase_success_between() {
+        let start = datetime!(1970-01-01 01:01:00 UTC);
+        let end = datetime!(1970-01-01 01:01:02.5 UTC);
+
+        let tc = TestCase::success_between("good test", start, end);
+        assert_eq!(tc.time, Duration::milliseconds(2500));
+        assert_eq!(tc.timestamp, Some(start));
+
+        let tc = TestCase::success_between("inverted test", end, start);
+        assert_eq!(tc.time, Duration::ZERO);
+        assert_eq!(tc.timestamp, Some(end));
+
+        let r = ReportBuilder::new()
+            .add_testsuite(
+                TestSuiteBuilder::new("ts1")
+                    .add_testcase(TestCase::success_between("good test", start, end))
+                    .build(),
+            )
+            .build();
+        let mut out: Vec<u8> = Vec::new();
+        r.write_xml(&mut out).unwrap();
+        assert!(String::from_utf8(out.clone()).unwrap().contains(
+            r#"<testcase name="good test" time="2.5" timestamp="1970-01-01T01:01:00Z"/>"#
+        ));
+        assert_eq!(r.validate_against_xsd(), Ok(()));
+        assert_eq!(Report::from_reader(out.as_slice()).unwrap(), r);
+    }
+
+    #[test]
+    fn format_seconds() {
+        use crate::reports::format_seconds;
+
+        for (secs, places, expected) in [
+            (0.0, None, "0"),
+            (1.5, None, "1.5"),
+            (0.001, None, "0.001"),
+            (0.000_000_1, None, "0.0000001"),
+            (12_345_678.25, None, "12345678.25"),
+            (1e21, None, "1000000000000000000000"),
+            (0.0, Some(3), "0.000"),
+            (1.5, Some(0), "2"),
+            (12_345.678, Some(2), "12345.68"),
+            (0.000_4, Some(3), "0.000"),
+        ] {
+            assert_eq!(format_seconds(secs, places), expected, "{}", secs);
+        }
+    }
+
+    #[test]
+    fn filter() {
+        let timestamp = datetime!(1970-01-01 01:01 UTC);
+        let r = ReportBuilder::new()
+            .add_testsuites(
+                ["unit::a", "integration::b", "unit::c", "integration::d"]
+                    .map(|name| TestSuiteBuilder::new(name).set_timestamp(timestamp).build()),
+            )
+            .build();
+
+        let filtered = r.filter(|ts| ts.name.starts_with("integration::"));
+        assert_eq!(r.testsuites().len(), 4);
+
+        let mut out: Vec<u8> = Vec::new();
+        filtered.write_xml(&mut out).unwrap();
+        // language=xml
+        let expected = "\
+<?xml version=\"1.0\" encoding=\"utf-8\"?>\
+<testsuites>\
+<testsuite id=\"0\" name=\"integration::b\" package=\"testsuite/integration::b\" tests=\"0\" errors=\"0\" failures=\"0\" skipped=\"0\" hostname=\"localhost\" timestamp=\"1970-01-01T01:01:00Z\" time=\"0\"/>\
+<testsuite id=\"1\" name=\"integration::d\" package=\"testsuite/integration::d\" tests=\"0\" errors=\"0\" failures=\"0\" skipped=\"0\" hostname=\"localhost\" timestamp=\"1970-01-01T01:01:00Z\" time=\"0\"/>\
+</testsuites>";
+        assert_eq!(String::from_utf8(out).unwrap(), expected);
+    }
+
+    #[test]
+    fn write_xml_with_warnings() {
+        use crate::WriteWarning;
+
+        let mut ts = TestSuiteBuilder::new("ts1")
+            .set_timestamp(datetime!(1970-01-01 01:01 UTC))
+            .add_testcase(TestCase::success("good test", Duration::seconds(1)))
+            .add_testcase(TestCase::success("slow test", Duration::days(2)))
+            .add_testcase(TestCase::failure(
+                "failure test",
+                Duration::ZERO,
+                "assert_eq",
+                "not\u{1b}[31m equal \u{fffd}\u{10ffff}",
+            ))
+            .build();
+        ts.add_property("key\u{0}", "val\u{fffe}ue\u{ffff}");
+        let r = ReportBuilder::new().add_testsuite(ts).build();
+
+        let options = ReportOptions {
+            max_time: Some(Duration::hours(1)),
+            ..ReportOptions::default()
+        };
+        let mut out: Vec<u8> = Vec::new();
+        let warnings = r.write_xml_with_warnings(&mut out, &options).unwrap();
+        assert_eq!(
+            warnings,
+            [
+                WriteWarning::ClampedTime {
+                    suite_index: Some(0),
+                    testcase_index: None,
+                },
+                WriteWarning::StrippedCharacters {
+                    suite_index: Some(0),
+                    testcase_index: None,
+                },
+                WriteWarning::ClampedTime {
+                    suite_index: Some(0),
+                    testcase_index: Some(1),
+                },
+                WriteWarning::StrippedCharacters {
+                    suite_index: Some(0),
+                    testcase_index: Some(2),
+                },
+            ]
+        );
+        assert_eq!(
+            warnings[3].to_string(),
+            "stripped invalid characters of testcase 2 of testsuite 0"
+        );
+
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.contains(r#"<property name="key" value="value"/>"#));
+        assert!(out.contains("message=\"not[31m equal \u{fffd}\u{10ffff}\""));
+        assert!(out.contains(r#"<testcase name="slow test" time="3600"/>"#));
+
+        let mut out: Vec<u8> = Vec::new();
+        let warnings = r
+            .write_xml_with_warnings(&mut out, &ReportOptions::default())
+            .unwrap();
+        assert_eq!(warnings.len(), 2);
+    }
+
+    #[test]
+    fn parse_foreign_report() {
+        // language=xml
+        let xml = "\
+<?xml version=\"1.0\" encoding=\"UTF-8\"?>\
+<testsuite name=\"pytest\" tests=\"3\" skipped=\"1\" timestamp=\"2024-01-01T10:00:00+01:00\" hostname=\"ci\" unknown=\"x\">\
+<testcase classname=\"tests.test_a\" name=\"test_ok\" time=\"0.5\" file=\"tests/test_a.py\" line=\"3\"/>\
+<testcase classname=\"tests.test_a\" name=\"test_fail\" time=\"1\">\
+<failure message=\"assert 1 == 2\">trace</failure>\
+<system-out>out</system-out>\
+<system-err>err</system-err>\
+</testcase>\
+<testcase classname=\"tests.test_a\" name=\"test_skip\" time=\"0\"><skipped message=\"no network\"/></testcase>\
+</testsuite>";
+        let r = Report::from_reader(xml.as_bytes()).unwrap();
+
+        let ts = &r.testsuites()[0];
+        assert_eq!(ts.name, "pytest");
+        assert_eq!(ts.hostname, "ci");
+        assert_eq!(ts.timestamp, Some(datetime!(2024-01-01 10:00 +1)));
+        let tc = &ts.testcases[0];
+        assert_eq!(tc.classname.as_deref(), Some("tests.test_a"));
+        assert_eq!(tc.filepath.as_deref(), Some("tests/test_a.py"));
+        assert_eq!(tc.time, Duration::milliseconds(500));
+        let tc = &ts.testcases[1];
+        assert_eq!(tc.failure_message(), Some("assert 1 == 2"));
+        assert_eq!(tc.system_out, ["out"]);
+        assert_eq!(tc.system_err, ["err"]);
+        assert!(ts.testcases[2].is_skipped());
+
+        let malformed = xml.replace(r#"time="0.5""#, r#"time="0,5""#);
+        assert!(matches!(
+            Report::from_reader(malformed.as_bytes()),
+            Err(Error::Parse(_))
+        ));
+        let malformed = xml.replace(r#"line="3""#, r#"line="x""#);
+        assert!(matches!(
+            Report::from_reader(malformed.as_bytes()),
+            Err(Error::Parse(_))
+        ));
+        let malformed = xml.replacen("<testcase", "<testcase <", 1);
+        assert!(matches!(
+            Report::from_reader(malformed.as_bytes()),
+            Err(Error::Xml(_))
+        ));
+    }
+
+    #[test]
+    fn report_from_str() {
+        let r = ReportBuilder::new()
+            .add_testsuite(
+                TestSuiteBuilder::new("ts1")
+                    .add_testcase(TestCase::success("good test", Duration::seconds(1)))
+                    .build(),
+            )
+            .build();
+        let mut out: Vec<u8> = Vec::new();
+        r.write_xml(&mut out).unwrap();
+
+        let parsed: Report = String::from_utf8(out).unwrap().parse().unwrap();
+        assert_eq!(parsed, r);
+        assert!(matches!("".parse::<Report>(), Err(Error::Parse(_))));
+    }
+
+    #[test]
+    fn testsuite_properties_before_testcases() {
+        let timestamp = datetime!(1970-01-01 01:01 UTC);
+
+        let ts1 = TestSuiteBuilder::new("ts1")
+            .set_timestamp(timestamp)
+            .add_testcase(TestCase::success("good test", Duration::ZERO))
+            .add_property("z", "1")
+            .add_property("a", "2")
+            .build();
+        let ts2 = TestSuiteBuilder::new("ts2")
+            .set_timestamp(timestamp)
+            .add_testcase(TestCase::success("good test", Duration::ZERO))
+            .build();
+
+        let r = ReportBuilder::new().add_testsuites([ts1, ts2]).build();
+
+        let mut out: Vec<u8> = Vec::new();
+        r.write_xml(&mut out).unwrap();
+
+        // language=xml
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "\
+<?xml version=\"1.0\" encoding=\"utf-8\"?>\
+<testsuites>\
+  <testsuite id=\"0\" name=\"ts1\" package=\"testsuite/ts1\" tests=\"1\" errors=\"0\" failures=\"0\" skipped=\"0\" hostname=\"localhost\" timestamp=\"1970-01-01T01:01:00Z\" time=\"0\">\
+    <properties>\
+      <property name=\"z\" value=\"1\"/>\
+      <property name=\"a\" value=\"2\"/>\
+    </properties>\
+    <testcase name=\"good test\" time=\"0\"/>\
+  </testsuite>\
+  <testsuite id=\"1\" name=\"ts2\" package=\"testsuite/ts2\" tests=\"1\" errors=\"0\" failures=\"0\" skipped=\"0\" hostname=\"localhost\" timestamp=\"1970-01-01T01:01:00Z\" time=\"0\">\
+    <testcase name=\"good test\" time=\"0\"/>\
+  </testsuite>\
+</testsuites>",
+        );
+    }
+
+    #[test]
+    fn testcase_properties_before_result() {
+        let mut tc = TestCase::failure("failure test", Duration::ZERO, "assert_eq", "not equal");
+        tc.add_property("seed", "42");
+        tc.set_system_out("out");
+        let r = ReportBuilder::new()
+            .add_testsuite(
+                TestSuiteBuilder::new("ts1")
+                    .set_timestamp(datetime!(1970-01-01 01:01 UTC))
+                    .add_testcase(tc)
+                    .build(),
+            )
+            .build();
+
+        let mut out: Vec<u8> = Vec::new();
+        r.write_xml(&mut out).unwrap();
+        assert!(String::from_utf8(out).unwrap().contains(
+            "\
+<testcase name=\"failure test\" time=\"0\">\
+<properties><property name=\"seed\" value=\"42\"/></properties>\
+<failure type=\"assert_eq\" message=\"not equal\"/>\
+<system-out><![CDATA[out]]></system-out>\
+</testcase>"
+        ));
+        assert_eq!(r.validate_against_xsd(), Ok(()));
+    }
+
+    #[test]
+    fn skipped_with_message() {
+        let r = ReportBuilder::new()
+            .add_testsuite(
+                TestSuiteBuilder::new("ts1")
+                    .set_timestamp(datetime!(1970-01-01 01:01 UTC))
+                    .add_testcase(TestCase::skipped_with_message("skipped test", "no network"))
+                    .add_testcase(TestCase::skipped("other test"))
+                    .build(),
+            )
+            .build();
+        let ts = &r.testsuites()[0];
+        assert!(ts.testcases[0].is_skipped());
+        assert_eq!(ts.skipped(), 2);
+        assert_eq!(ts.testcases[0].result.to_string(), "skipped: no network");
+
+        let mut out: Vec<u8> = Vec::new();
+        r.write_xml(&mut out).unwrap();
+        // language=xml
+        let expected = "\
+<?xml version=\"1.0\" encoding=\"utf-8\"?>\
+<testsuites>\
+<testsuite id=\"0\" name=\"ts1\" package=\"testsuite/ts1\" tests=\"2\" errors=\"0\" failures=\"0\" skipped=\"2\" hostname=\"localhost\" timestamp=\"1970-01-01T01:01:00Z\" time=\"0\">\
+<testcase name=\"skipped test\" time=\"0\"><skipped message=\"no network\"/></testcase>\
+<testcase name=\"other test\" time=\"0\"><skipped/></testcase>\
+</testsuite>\
+</testsuites>";
+        assert_eq!(String::from_utf8(out.clone()).unwrap(), expected);
+        assert_eq!(r.validate_against_xsd(), Ok(()));
+        assert_eq!(Report::from_reader(out.as_slice()).unwrap(), r);
+    }
+
+    #[test]
+    fn multiple_failures() {
+        let r = ReportBuilder::new()
+            .add_testsuite(
+                TestSuiteBuilder::new("ts1")
+                    .set_timestamp(datetime!(1970-01-01 01:01 UTC))
+                    .add_testcase(
+                        TestCaseBuilder::success("flaky test", Duration::ZERO)
+                            .add_failure("assert_eq", "attempt 1")
+                            .add_failure("assert_eq", "attempt 2")
+                            .build(),
+                    )
+                    .add_testcase(TestCase::failure(
+                        "failure test",
+                        Duration::ZERO,
+                        "assert_eq",
+                        "not equal",
+                    ))
+                    .build(),
+            )
+            .build();
+        let ts = &r.testsuites()[0];
+        assert_eq!(ts.failures(), 2);
+        assert_eq!(ts.testcases[0].additional_results().len(), 1);
+
+        let mut out: Vec<u8> = Vec::new();
+        r.write_xml(&mut out).unwrap();
+        assert!(String::from_utf8(out.clone()).unwrap().contains(
+            "\
+<testcase name=\"flaky test\" time=\"0\">\
+<failure type=\"assert_eq\" message=\"attempt 1\"/>\
+<failure type=\"assert_eq\" message=\"attempt 2\"/>\
+</testcase>"
+        ));
+        assert_eq!(r.validate_against_xsd(), Ok(()));
+        assert_eq!(Report::from_reader(out.as_slice()).unwrap(), r);
+    }
+
+    #[test]
+    fn report_name_with_totals() {
+        let r = ReportBuilder::new()
+            .set_name("all tests")
+            .add_testsuite(
+                TestSuiteBuilder::new("ts1")
+                    .set_timestamp(datetime!(1970-01-01 01:01 UTC))
+                    .add_testcase(TestCase::success("good test", Duration::seconds(1)))
+                    .add_testcase(TestCase::failure(
+                        "failure test",
+                        Duration::seconds(2),
+                        "assert_eq",
+                        "not equal",
+                    ))
+                    .build(),
+            )
+            .build();
+
+        let mut out: Vec<u8> = Vec::new();
+        r.write_xml(&mut out).unwrap();
+        assert!(String::from_utf8(out.clone()).unwrap().contains(
+            r#"<testsuites name="all tests" tests="2" failures="1" errors="0" time="3">"#
+        ));
+        assert_eq!(r.validate_against_xsd(), Ok(()));
+        assert_eq!(Report::from_reader(out.as_slice()).unwrap(), r);
+
+        let mut out: Vec<u8> = Vec::new();
+        ReportBuilder::new()
+            .set_name("empty")
+            .build()
+            .write_xml(&mut out)
+            .unwrap();
+        assert!(String::from_utf8(out)
+            .unwrap()
+            .ends_with(r#"<testsuites name="empty" tests="0" failures="0" errors="0" time="0"/>"#));
+    }
+
+    #[test]
+    fn testcase_assertions_attribute() {
+        let r = ReportBuilder::new()
+            .add_testsuite(
+                TestSuiteBuilder::new("ts1")
+                    .add_testcase(
+                        TestCaseBuilder::success("good test", Duration::seconds(1))
+                            .set_classname("MyClass")
+                            .set_assertions(3)
+                            .build(),
+                    )
+                    .add_testcase(TestCase::success("other test", Duration::ZERO))
+                    .build(),
+            )
+            .build();
+
+        let mut out: Vec<u8> = Vec::new();
+        r.write_xml(&mut out).unwrap();
+        assert!(String::from_utf8(out.clone()).unwrap().contains(
+            r#"<testcase name="good test" time="1" assertions="3" classname="MyClass"/><testcase name="other test" time="0"/>"#
+        ));
+        assert_eq!(r.validate_against_xsd(), Ok(()));
+        assert_eq!(Report::from_reader(out.as_slice()).unwrap(), r);
+    }
+
+    #[test]
+    fn to_xml_string() {
+        let r = ReportBuilder::new()
+            .add_testsuite(
+                TestSuiteBuilder::new("ts1")
+                    .add_testcase(TestCase::success("good test", Duration::seconds(1)))
+                    .build(),
+            )
+            .build();
+
+        let mut out: Vec<u8> = Vec::new();
+        r.write_xml(&mut out).unwrap();
+        assert_eq!(r.to_xml_string().unwrap(), String::from_utf8(out).unwrap());
+    }
+
+    #[test]
+    fn data_types_are_eq() {
+        fn assert_eq_impl<T: Eq>() {}
+        assert_eq_impl::<Report>();
+        assert_eq_impl::<TestSuite>();
+        assert_eq_impl::<TestCase>();
+        assert_eq_impl::<crate::TestResult>();
+        assert_eq_impl::<crate::Property>();
+        assert_eq_impl::<Summary>();
+
+        let tc = TestCase::success("good test", Duration::seconds(1));
+        let mut cases = vec![tc.clone(), tc.clone(), TestCase::skipped("skipped test")];
+        cases.dedup();
+        assert_eq!(cases.len(), 2);
+    }
+
+    #[test]
+    fn write_xml_pretty_and_compact() {
+        let r = ReportBuilder::new()
+            .add_testsuite(
+                TestSuiteBuilder::new("ts1")
+                    .set_timestamp(datetime!(1970-01-01 01:01 UTC))
+                    .add_testcase(TestCase::success("good test", Duration::ZERO))
+                    .build(),
+            )
+            .build();
+
+        let mut out: Vec<u8> = Vec::new();
+        r.write_xml_pretty(&mut out).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "<?xml version=\"1.0\" encoding=\"utf-8\"?>
+<testsuites>
+  <testsuite id=\"0\" name=\"ts1\" package=\"testsuite/ts1\" tests=\"1\" errors=\"0\" failures=\"0\" skipped=\"0\" hostname=\"localhost\" timestamp=\"1970-01-01T01:01:00Z\" time=\"0\">
+    <testcase name=\"good test\" time=\"0\"/>
+  </testsuite>
+</testsuites>"
+        );
+
+        let mut compact: Vec<u8> = Vec::new();
+        r.write_xml_compact(&mut compact).unwrap();
+        assert_eq!(
+            String::from_utf8(compact).unwrap(),
+            r.to_xml_string().unwrap()
+        );
+    }
+
+    #[test]
+    fn write_xml_tab_indent() {
+        let r = ReportBuilder::new()
+            .add_testsuite(
+                TestSuiteBuilder::new("ts1")
+                    .set_timestamp(datetime!(1970-01-01 01:01 UTC))
+                    .add_testcase(TestCase::success("good test", Duration::ZERO))
+                    .build(),
+            )
+            .build();
+
+        let options = ReportOptions::default().with_indent(b'\t', 1);
+        let mut out: Vec<u8> = Vec::new();
+        r.write_xml_with(&mut out, &options).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "<?xml version=\"1.0\" encoding=\"utf-8\"?>
+<testsuites>
+\t<testsuite id=\"0\" name=\"ts1\" package=\"testsuite/ts1\" tests=\"1\" errors=\"0\" failures=\"0\" skipped=\"0\" hostname=\"localhost\" timestamp=\"1970-01-01T01:01:00Z\" time=\"0\">
+\t\t<testcase name=\"good test\" time=\"0\"/>
+\t</testsuite>
+</testsuites>"
+        );
+    }
+
+    #[test]
+    fn testsuite_filepath() {
+        let ts = TestSuiteBuilder::new("ts1")
+            .set_timestamp(datetime!(1970-01-01 01:01 UTC))
+            .set_filepath("tests/foo.rs")
+            .build();
+        let r = ReportBuilder::new()
+            .add_testsuite(ts)
+            .add_testsuite(
+                TestSuiteBuilder::new("ts2")
+                    .set_timestamp(datetime!(1970-01-01 01:01 UTC))
+                    .build(),
+            )
+            .build();
+
+        let xml = r.to_xml_string().unwrap();
+        assert_eq!(
+            xml,
+            // language=xml
+            "\
+            <?xml version=\"1.0\" encoding=\"utf-8\"?>\
+            <testsuites>\
+                <testsuite id=\"0\" name=\"ts1\" package=\"testsuite/ts1\" tests=\"0\" errors=\"0\" failures=\"0\" skipped=\"0\" hostname=\"localhost\" file=\"tests/foo.rs\" timestamp=\"1970-01-01T01:01:00Z\" time=\"0\"/>\
+                <testsuite id=\"1\" name=\"ts2\" package=\"testsuite/ts2\" tests=\"0\" errors=\"0\" failures=\"0\" skipped=\"0\" hostname=\"localhost\" timestamp=\"1970-01-01T01:01:00Z\" time=\"0\"/>\
+            </testsuites>"
+        );
+        assert_eq!(xml.parse::<Report>().unwrap(), r);
+    }
+
+    #[test]
+    fn testcase_line() {
+        let tc = TestCaseBuilder::success("good test", Duration::ZERO)
+            .set_filepath("tests/foo.rs")
+            .set_line(42)
+            .build();
+        let r = ReportBuilder::new()
+            .add_testsuite(
+                TestSuiteBuilder::new("ts1")
+                    .set_timestamp(datetime!(1970-01-01 01:01 UTC))
+                    .add_testcase(tc)
+                    .add_testcase(TestCase::success("other test", Duration::ZERO).with_line(7))
+                    .build(),
+            )
+            .build();
+
+        let xml = r.to_xml_string().unwrap();
+        assert_eq!(
+            xml,
+            // language=xml
+            "\
+            <?xml version=\"1.0\" encoding=\"utf-8\"?>\
+            <testsuites>\
+                <testsuite id=\"0\" name=\"ts1\" package=\"testsuite/ts1\" tests=\"2\" errors=\"0\" failures=\"0\" skipped=\"0\" hostname=\"localhost\" timestamp=\"1970-01-01T01:01:00Z\" time=\"0\">\
+                    <testcase name=\"good test\" time=\"0\" file=\"tests/foo.rs\" line=\"42\"/>\
+                    <testcase name=\"other test\" time=\"0\" line=\"7\"/>\
+                </testsuite>\
+            </testsuites>"
+        );
+        assert_eq!(xml.parse::<Report>().unwrap(), r);
+    }
+
+    #[test]
+    fn testsuite_without_timestamp() {
+        let r = ReportBuilder::new()
+            .add_testsuite(TestSuiteBuilder::new("ts1").without_timestamp().build())
+            .build();
+        assert_eq!(r.testsuites()[0].timestamp(), &None);
+
+        let xml = r.to_xml_string().unwrap();
+        assert_eq!(
+            xml,
+            // language=xml
+            "\
+            <?xml version=\"1.0\" encoding=\"utf-8\"?>\
+            <testsuites>\
+                <testsuite id=\"0\" name=\"ts1\" package=\"testsuite/ts1\" tests=\"0\" errors=\"0\" failures=\"0\" skipped=\"0\" hostname=\"localhost\" time=\"0\"/>\
+            </testsuites>"
+        );
+        assert_eq!(xml.parse::<Report>().unwrap(), r);
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn chrono_timestamp() {
+        let timestamp = ::chrono::DateTime::from_timestamp(3661, 500_000_000).unwrap();
+
+        let ts = TestSuiteBuilder::new("ts1")
+            .set_timestamp_chrono(timestamp)
+            .build();
+        assert_eq!(ts.timestamp, Some(datetime!(1970-01-01 01:01:01.5 UTC)));
+
+        let mut ts = TestSuite::new("ts1");
+        ts.set_timestamp_chrono(timestamp);
+        let r = ReportBuilder::new().add_testsuite(ts).build();
+        assert!(r
+            .to_xml_string()
+            .unwrap()
+            .contains("timestamp=\"1970-01-01T01:01:01.5Z\""));
+
+        let mut ts = TestSuite::new("ts1");
+        ts.set_timestamp_chrono(::chrono::DateTime::<::chrono::Utc>::MAX_UTC);
+        assert_eq!(
+            ts.timestamp,
+            Some(datetime!(9999-12-31 23:59:59.999_999_999 UTC))
+        );
+
+        let before_year_0 = ::chrono::NaiveDate::from_ymd_opt(-100, 6, 1)
+            .unwrap()
+            .and_hms_opt(12, 0, 0)
+            .unwrap()
+            .and_utc();
+        let ts = TestSuiteBuilder::new("ts1")
+            .set_timestamp_chrono(before_year_0)
+            .build();
+        assert_eq!(ts.timestamp, Some(datetime!(0000-01-01 0:00 UTC)));
+        let r = ReportBuilder::new().add_testsuite(ts).build();
+        assert!(r
+            .to_xml_string()
+            .unwrap()
+            .contains("timestamp=\"0000-01-01T00:00:00Z\""));
+    }
+
+    #[test]
+    fn merge_reports() {
+        let mut r1 = ReportBuilder::new()
+            .set_name("shard1")
+            .add_testsuite(TestSuite::new("ts1"))
+            .build();
+        let r2 = ReportBuilder::new()
+            .set_name("shard2")
+            .add_testsuite(TestSuite::new("ts2"))
+            .add_testsuite(TestSuite::new("ts3"))
+            .build();
+
+        let merged = Report::merge_all([r1.clone(), r2.clone()]);
+        r1.merge(r2);
+        assert_eq!(merged, r1);
+        assert_eq!(r1.name().as_deref(), Some("shard1"));
+        let names: Vec<_> = r1.testsuites().iter().map(|ts| ts.name.as_str()).collect();
+        assert_eq!(names, ["ts1", "ts2", "ts3"]);
+
+        assert_eq!(Report::merge_all([]), Report::new());
+    }
+
+    #[test]
+    fn unwritable_timestamp() {
+        let ts = TestSuiteBuilder::new("ts1")
+            .set_timestamp(datetime!(-0001-01-01 0:00 UTC))
+            .build();
+        let r = ReportBuilder::new().add_testsuite(ts).build();
+        assert!(matches!(r.to_xml_string(), Err(Error::Validation(_))));
+        // The Ant format has no restriction on the year
+        let mut out: Vec<u8> = Vec::new();
+        r.write_xml_with(&mut out, &ReportOptions::ant()).unwrap();
+        assert!(String::from_utf8(out)
+            .unwrap()
+            .contains("timestamp=\"-0001-01-01T00:00:00\""));
+
+        let mut tc = TestCase::success("tc", Duration::ZERO);
+        tc.set_timestamp(datetime!(-0001-01-01 0:00 UTC));
+        let r = ReportBuilder::new()
+            .add_testsuite(TestSuiteBuilder::new("ts1").add_testcase(tc).build())
+            .build();
+        assert!(matches!(r.to_xml_string(), Err(Error::Validation(_))));
+        // The Ant dialect does not write the timestamp of test cases
+        assert!(r.write_xml_with(Vec::new(), &ReportOptions::ant()).is_ok());
+    }
+
+    #[cfg(feature = "ctrf")]
+    #[test]
+    fn ctrf_json_huge_time() {
+        let ts = TestSuiteBuilder::new("ts1")
+            .set_timestamp(datetime!(2024-01-01 0:00 UTC))
+            .add_testcase(TestCase::success(
+                "t",
+                Duration::seconds(1_000_000_000_000_000),
+            ))
+            .build();
+        let r = ReportBuilder::new().add_testsuite(ts).build();
+
+        let mut out: Vec<u8> = Vec::new();
+        r.write_ctrf_json(&mut out).unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        assert_eq!(json["results"]["summary"]["stop"], 253_402_300_799_999_i64);
+    }
+
+    #[cfg(feature = "junit5")]
+    #[test]
+    fn open_test_reporting_huge_time() {
+        let huge = Duration::seconds(i64::MAX / 2);
+        let ts = TestSuiteBuilder::new("ts1")
+            .set_timestamp(datetime!(2024-01-01 0:00 UTC))
+            .add_testcase(TestCase::success("t1", huge))
+            .add_testcase(TestCase::success("t2", huge))
+            .add_testcase(TestCase::success("t3", huge))
+            .build();
+        assert_eq!(ts.time(), Duration::MAX);
+        let r = ReportBuilder::new().add_testsuite(ts).build();
+
+        let mut out: Vec<u8> = Vec::new();
+        r.write_open_test_reporting(&mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert_eq!(
+            out.matches("time=\"9999-12-31T23:59:59.999999999Z\"")
+                .count(),
+            6
+        );
+    }
+
+    #[test]
+    fn write_warnings_only_for_written_content() {
+        use crate::WriteWarning;
+
+        let ts = TestSuiteBuilder::new("ts1")
+            .add_testcase(TestCase::success("slow test", Duration::days(2)))
+            .add_testcase(
+                TestCaseBuilder::failure("failure test", Duration::ZERO, "type", "message")
+                    .set_system_out("out\u{1b}")
+                    .build(),
+            )
+            .build();
+        let r = ReportBuilder::new().add_testsuite(ts).build();
+        let clamped = |suite_index, testcase_index| WriteWarning::ClampedTime {
+            suite_index,
+            testcase_index,
+        };
+        let stripped = |suite_index, testcase_index| WriteWarning::StrippedCharacters {
+            suite_index,
+            testcase_index,
+        };
+
+        let options = ReportOptions {
+            max_time: Some(Duration::hours(1)),
+            ..ReportOptions::default()
+        };
+        let warnings = r.write_xml_with_warnings(Vec::new(), &options).unwrap();
+        assert_eq!(
+            warnings,
+            [
+                clamped(Some(0), None),
+                clamped(Some(0), Some(0)),
+                stripped(Some(0), Some(1)),
+            ]
+        );
+
+        // The time of test cases is not written
+        let omit = ReportOptions {
+            testcase_time: TestCaseTime::Omit,
+            ..options.clone()
+        };
+        let warnings = r.write_xml_with_warnings(Vec::new(), &omit).unwrap();
+        assert_eq!(
+            warnings,
+            [clamped(Some(0), None), stripped(Some(0), Some(1))]
+        );
+
+        // Ant does not write the output of test cases
+        let ant = ReportOptions {
+            max_time: Some(Duration::hours(1)),
+            ..ReportOptions::ant()
+        };
+        let warnings = r.write_xml_with_warnings(Vec::new(), &ant).unwrap();
+        assert_eq!(
+            warnings,
+            [clamped(Some(0), None), clamped(Some(0), Some(0))]
+        );
+
+        // pytest does not write the type of failures
+        let tc = TestCase::failure("failure test", Duration::ZERO, "type\u{1}", "message");
+        let r = ReportBuilder::new()
+            .add_testsuite(TestSuiteBuilder::new("ts1").add_testcase(tc).build())
+            .build();
+        let warnings = r
+            .write_xml_with_warnings(Vec::new(), &ReportOptions::pytest())
+            .unwrap();
+        assert_eq!(warnings, []);
+        let warnings = r
+            .write_xml_with_warnings(Vec::new(), &ReportOptions::default())
+            .unwrap();
+        assert_eq!(warnings, [stripped(Some(0), Some(0))]);
+    }
+
+    #[test]
+    fn write_negative_time() {
+        use crate::WriteWarning;
+
+        let r = ReportBuilder::new()
+            .add_testsuite(
+                TestSuiteBuilder::new("ts1")
+                    .add_testcase(TestCase::success("good test", Duration::seconds(1)))
+                    .add_testcase(TestCase::success("bad clock", Duration::seconds(-2)))
+                    .build(),
+            )
+            .build();
+
+        let mut out: Vec<u8> = Vec::new();
+        let warnings = r
+            .write_xml_with_warnings(&mut out, &ReportOptions::default())
+            .unwrap();
+        assert_eq!(
+            warnings,
+            [
+                WriteWarning::ClampedTime {
+                    suite_index: Some(0),
+                    testcase_index: None,
+                },
+                WriteWarning::ClampedTime {
+                    suite_index: Some(0),
+                    testcase_index: Some(1),
+                },
+            ]
+        );
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.contains(r#"<testcase name="bad clock" time="0"/>"#));
+        assert!(out.contains(r#"skipped="0" hostname="localhost""#));
+    }
+
+    #[test]
+    fn add_failure_at_keeps_previous_failures() {
+        let tc = TestCaseBuilder::success("flaky test", Duration::ZERO)
+            .add_failure("assert_eq", "first attempt")
+            .add_failure_at("assert_eq", "second attempt", "src/lib.rs", 42)
+            .build();
+
+        assert_eq!(tc.failure_message(), Some("first attempt"));
+        assert_eq!(
+            tc.additional_results,
+            [crate::TestResult::Failure {
+                type_: "assert_eq".into(),
+                message: "second attempt".into(),
+                cause: None,
+                file: Some("src/lib.rs".into()),
+                line: Some(42),
+            }]
+        );
+    }
+
+    #[test]
+    fn parse_testsuite_time() {
+        let mut ts = TestSuiteBuilder::new("ts1")
+            .add_testcase(TestCase::success("good test", Duration::milliseconds(1500)))
+            .build();
+        ts.set_time(Duration::seconds(10));
+        let empty = TestSuiteBuilder::new("ts2")
+            .set_time(Duration::milliseconds(250))
+            .build();
+        let r = ReportBuilder::new()
+            .add_testsuite(ts)
+            .add_testsuite(empty)
+            .build();
+
+        let parsed: Report = r.to_xml_string().unwrap().parse().unwrap();
+        assert_eq!(parsed, r);
+
+        // The sum of the test case times, up to the rounding of the writer
+        let xml = r#"<testsuite name="ts1" tests="2" time="0.3">
+            <testcase name="t1" time="0.1"/><testcase name="t2" time="0.2"/>
+        </testsuite>"#;
+        let parsed: Report = xml.parse().unwrap();
+        assert_eq!(parsed.testsuites()[0].time_override, None);
+        let xml = xml.replace("time=\"0.3\"", "time=\"0.300\"");
+        let parsed: Report = xml.parse().unwrap();
+        assert_eq!(parsed.testsuites()[0].time_override, None);
+        let xml = xml.replace("time=\"0.300\"", "time=\"0.4\"");
+        let parsed: Report = xml.parse().unwrap();
+        assert_eq!(parsed.testsuites()[0].time(), Duration::milliseconds(400));
+
+        let xml = r#"<testsuite name="ts1" tests="0" time="abc"/>"#;
+        assert!(matches!(xml.parse::<Report>(), Err(Error::Parse(_))));
+    }
 }
@@ -0,0 +1,44 @@
+/*
+ * Copyright (c) 2018 Pascal Bach
+ * Copyright (c) 2021 Siemens Mobility GmbH
+ *
+ * SPDX-License-Identifier:     MIT
+ */
+
+/// Create a [`TestCase`](struct.TestCase.html) in a concise way.
+///
+/// The first token selects the constructor, the remaining arguments are passed on:
+///
+/// | Form                                              | Expands to                                          |
+/// |---------------------------------------------------|-----------------------------------------------------|
+/// | `testcase!(success name, time)`                   | [`TestCase::success`](struct.TestCase.html#method.success)`(name, time)` |
+/// | `testcase!(error name, time, type_, message)`     | [`TestCase::error`](struct.TestCase.html#method.error)`(name, time, type_, message)` |
+/// | `testcase!(failure name, time, type_, message)`   | [`TestCase::failure`](struct.TestCase.html#method.failure)`(name, time, type_, message)` |
+/// | `testcase!(skipped name)`                         | [`TestCase::skipped`](struct.TestCase.html#method.skipped)`(name)` |
+///
+/// ```rust
+/// use junit_report::{secs, testcase};
+///
+/// let success = testcase!(success "good test", secs(1.0));
+/// let failure = testcase!(failure "failure test", secs(2.0), "assert_eq", "not equal");
+/// let skipped = testcase!(skipped "skipped test");
+///
+/// assert!(success.is_success());
+/// assert!(failure.is_failure());
+/// assert!(skipped.is_skipped());
+/// ```
+#[macro_export]
+macro_rules! testcase {
+    (success $name:expr, $time:expr $(,)?) => {
+        $crate::TestCase::success($name, $time)
+    };
+    (error $name:expr, $time:expr, $type_:expr, $message:expr $(,)?) => {
+        $crate::TestCase::error($name, $time, $type_, $message)
+    };
+    (failure $name:expr, $time:expr, $type_:expr, $message:expr $(,)?) => {
+        $crate::TestCase::failure($name, $time, $type_, $message)
+    };
+    (skipped $name:expr $(,)?) => {
+        $crate::TestCase::skipped($name)
+    };
+}
@@ -0,0 +1,237 @@
+/*
+ * Copyright (c) 2018 Pascal Bach
+ * Copyright (c) 2021 Siemens Mobility GmbH
+ *
+ * SPDX-License-Identifier:     MIT
+ */
+
+use std::path::PathBuf;
+
+use time::Duration;
+
+/// Options controlling how a [`Report`](struct.Report.html) is written.
+///
+/// The [`Default`] options produce the same output as
+/// [`Report::write_xml`](struct.Report.html#method.write_xml).
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct ReportOptions {
+    /// Number of decimal places used for `time` attributes.
+    ///
+    /// If `None` the shortest representation of the number of seconds is used.
+    pub time_precision: Option<usize>,
+    /// Unit of `time` attributes.
+    ///
+    /// The JUnit format uses seconds, some non-standard consumers expect milliseconds.
+    pub time_unit: TimeUnit,
+    /// Upper limit for `time` attributes, longer durations are written as this value.
+    ///
+    /// This protects consumers from absurd values of buggy callers, e.g. durations
+    /// near the limits of [`Duration`](struct.Duration.html) that also lose precision
+    /// when converted to seconds. A negative limit is treated as zero. If `None` the
+    /// time is written as is.
+    pub max_time: Option<Duration>,
+    /// Emit the aggregated `tests`, `failures`, `errors` and `time` attributes
+    /// on the root `<testsuites>` element. They are always written for reports with
+    /// a [`name`](struct.Report.html#method.set_name).
+    pub root_totals: bool,
+    /// Emit a `status` attribute on every `<testcase>`, `run` for executed
+    /// test cases and `notrun` for skipped ones.
+    pub testcase_status: bool,
+    /// Prefix removed from the `file` attribute of test suites and test cases, e.g. the workspace
+    /// root, to make the paths relative. Paths outside of the prefix are kept as is.
+    pub path_prefix_strip: Option<PathBuf>,
+    /// Replace backslashes in the `file` attribute of test suites and test cases with forward slashes.
+    ///
+    /// This is applied before `path_prefix_strip`.
+    pub path_forward_slashes: bool,
+    /// Fail with [`Error::Validation`](enum.Error.html#variant.Validation) instead of
+    /// writing a report where required attributes are empty.
+    ///
+    /// The name of every test suite, test case and property is required.
+    pub strict: bool,
+    /// Move `message`s of failures and errors that are longer than the given number
+    /// of characters or span multiple lines into the element body.
+    ///
+    /// The full message is written as CDATA in front of the trace and the `message`
+    /// attribute is shortened to the beginning of its first line.
+    pub long_message_to_body: Option<usize>,
+    /// Skip `<system-out>` and `<system-err>` elements whose content is an empty string,
+    /// as if the output was not set at all.
+    pub omit_empty_output: bool,
+    /// The set and order of attributes written for test suites, test cases and results.
+    pub dialect: Dialect,
+    /// The line ending used for all newlines in the report, including those in
+    /// messages, traces and output.
+    pub line_ending: LineEnding,
+    /// Indent nested elements with the given character and number of characters per
+    /// level, e.g. `Some((b' ', 2))`.
+    ///
+    /// If `None` the report is written on a single line.
+    pub indent: Option<(u8, usize)>,
+    /// Skip the `<?xml version="1.0" encoding="utf-8"?>` declaration, e.g. to embed
+    /// the report into another document.
+    pub omit_declaration: bool,
+    /// Write elements without content as `<element></element>` instead of `<element/>`.
+    pub expand_empty_elements: bool,
+    /// How output, traces and messages moved into the element body are written.
+    pub text_mode: TextMode,
+    /// When the `time` attribute of test cases is written.
+    pub testcase_time: TestCaseTime,
+    /// Name written on the root `<testsuites>` element of reports without a
+    /// [`name`](struct.Report.html#method.set_name).
+    ///
+    /// Like a report name it also enables the aggregated attributes of `root_totals`.
+    pub root_name: Option<String>,
+}
+
+/// When the `time` attribute of a `<testcase>` is written.
+///
+/// Test cases without `time` are read with a time of zero.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TestCaseTime {
+    /// For every test case
+    #[default]
+    Always,
+    /// For every test case but skipped and disabled ones with a time of zero,
+    /// as some tools do not write it for skipped tests
+    OmitZeroSkipped,
+    /// Never
+    Omit,
+}
+
+/// How text content like output and traces is written.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextMode {
+    /// As `<![CDATA[...]]>` sections
+    #[default]
+    Cdata,
+    /// As text with `&`, `<` and `>` escaped, for consumers that do not support CDATA
+    Escaped,
+}
+
+/// Options controlling how a [`Report`](struct.Report.html) is read, see
+/// [`Report::from_reader_with`](struct.Report.html#method.from_reader_with).
+///
+/// The [`Default`] options behave like
+/// [`Report::from_reader`](struct.Report.html#method.from_reader).
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct ParseOptions {
+    /// Unit of `time` attributes.
+    pub time_unit: TimeUnit,
+    /// Accept `timestamp` attributes that are not valid RFC 3339 but are written by
+    /// some tools, e.g. `2024-01-01 10:00:00`.
+    ///
+    /// A space may separate date and time and timestamps without UTC offset are
+    /// assumed to be in UTC. Otherwise such timestamps are rejected.
+    pub lenient_timestamps: bool,
+    /// Skip files that are not valid JUnit XML in
+    /// [`Report::from_dir_with`](struct.Report.html#method.from_dir_with) instead of failing.
+    pub skip_invalid_files: bool,
+}
+
+/// Line ending used when writing a report.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineEnding {
+    /// `\n`, as used on Unix
+    #[default]
+    Lf,
+    /// `\r\n`, as used on Windows
+    CrLf,
+}
+
+/// Unit of `time` attributes.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeUnit {
+    /// Seconds, as defined by the JUnit format
+    #[default]
+    Seconds,
+    /// Milliseconds, for non-standard consumers
+    Milliseconds,
+}
+
+/// The set and order of attributes expected by a consumer of the report.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dialect {
+    /// The attributes written by [`Report::write_xml`](struct.Report.html#method.write_xml).
+    #[default]
+    Default,
+    /// The attributes written by pytest's `--junitxml` with the default `xunit2` family,
+    /// see [`ReportOptions::pytest`].
+    Pytest,
+    /// The aggregated reports written by Apache Ant's `junitreport` task,
+    /// see [`ReportOptions::ant`].
+    Ant,
+}
+
+impl ReportOptions {
+    /// Indent nested elements with `indent_char` repeated `width` times per level,
+    /// e.g. `with_indent(b'\t', 1)` for tabs.
+    ///
+    /// This sets [`indent`](#structfield.indent), the default options write the report
+    /// on a single line.
+    pub fn with_indent(mut self, indent_char: u8, width: usize) -> Self {
+        self.indent = Some((indent_char, width));
+        self
+    }
+
+    /// Options for reports consumed by Bazel as `test.xml`.
+    ///
+    /// Bazel reads the totals from the root `<testsuites>` element and requires it to
+    /// have a `name`, which is `testsuites` for reports without a
+    /// [`name`](struct.Report.html#method.set_name). It expects the `status` attribute
+    /// on each `<testcase>` as written by its own test runners and `time` as a plain
+    /// decimal number of seconds with millisecond precision.
+    pub fn bazel() -> Self {
+        ReportOptions {
+            time_precision: Some(3),
+            root_totals: true,
+            testcase_status: true,
+            root_name: Some("testsuites".to_string()),
+            ..Default::default()
+        }
+    }
+
+    /// Options for reports that mimic the output of pytest's `--junitxml`.
+    ///
+    /// The attributes are written in the same order as pytest 7 does with its default
+    /// `xunit2` family and `time` uses millisecond precision:
+    ///
+    /// * `<testsuite>` has `name`, `errors`, `failures`, `skipped`, `tests`, `time`,
+    ///   `timestamp` and `hostname`, the `id` and `package` attributes are omitted.
+    /// * `<testcase>` has `classname`, `name` and `time`, `classname` is always written
+    ///   and the `file` attribute is omitted.
+    /// * `<failure>` and `<error>` only have a `message`, `type`, `file` and `line` are omitted.
+    /// * `<skipped>` has `type="pytest.skip"` and an empty `message`.
+    ///
+    /// Unlike pytest the `timestamp` always includes the UTC offset.
+    pub fn pytest() -> Self {
+        ReportOptions {
+            time_precision: Some(3),
+            dialect: Dialect::Pytest,
+            ..Default::default()
+        }
+    }
+
+    /// Options for reports that follow the Apache Ant JUnit schema.
+    ///
+    /// This matches the aggregated `TESTS-TestSuites.xml` of Ant's `junitreport` task,
+    /// which is also described by `tests/JUnit-alternative.xsd`:
+    ///
+    /// * `<testsuite>` keeps `id` and `package`, which Ant uses to group suites. Set
+    ///   the package with [`TestSuite::set_package`](struct.TestSuite.html#method.set_package)
+    ///   to replace the derived `testsuite/{name}`.
+    /// * `timestamp` is written without fractional seconds and UTC offset. Such reports
+    ///   can only be read with [`ParseOptions::lenient_timestamps`], which assumes the
+    ///   timestamps to be in UTC.
+    /// * `<properties>`, `<system-out>` and `<system-err>` are always written for a suite,
+    ///   several output blocks are concatenated into one element.
+    /// * `<testcase>` has `name`, `classname` and `time`, `classname` is always written.
+    ///   The `file` attribute and the output of test cases are omitted.
+    /// * `<failure>` and `<error>` have `type` and `message`, `file` and `line` are omitted.
+    pub fn ant() -> Self {
+        ReportOptions {
+            dialect: Dialect::Ant,
+            ..Default::default()
+        }
+    }
+}
@@ -0,0 +1,533 @@
+/*
+ * Copyright (c) 2018 Pascal Bach
+ * Copyright (c) 2021 Siemens Mobility GmbH
+ *
+ * SPDX-License-Identifier:     MIT
+ */
+
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader, Read};
+use std::path::Path;
+use std::str::FromStr;
+
+use quick_xml::{
+    events::{BytesStart, Event},
+    Reader,
+};
+use time::{
+    format_description::well_known::Rfc3339, macros::format_description, Duration, OffsetDateTime,
+    PrimitiveDateTime,
+};
+
+use crate::reports::format_seconds;
+use crate::{Error, ParseOptions, Property, Report, TestCase, TestResult, TestSuite, TimeUnit};
+
+impl Report {
+    /// Read a Report from the JUnit XML provided by `reader`.
+    ///
+    /// Both `<testsuites>` and a single `<testsuite>` are accepted as root element.
+    /// Unknown elements and attributes are ignored. Timestamps keep their UTC offset,
+    /// so they are written back unchanged.
+    ///
+    /// Malformed XML fails with [`Error::Xml`], while a malformed `time`, `timestamp`
+    /// or numeric attribute in otherwise well-formed XML fails with [`Error::Parse`].
+    pub fn from_reader<R: Read>(reader: R) -> Result<Report, Error> {
+        Report::from_reader_with(reader, &ParseOptions::default())
+    }
+
+    /// Same as [`from_reader`](#method.from_reader) but interprets `time` attributes
+    /// in the given [`TimeUnit`](enum.TimeUnit.html).
+    ///
+    /// This allows to read reports of non-standard tools writing milliseconds.
+    pub fn from_reader_with_time_unit<R: Read>(
+        reader: R,
+        time_unit: TimeUnit,
+    ) -> Result<Report, Error> {
+        let options = ParseOptions {
+            time_unit,
+            ..Default::default()
+        };
+        Report::from_reader_with(reader, &options)
+    }
+
+    /// Same as [`from_reader`](#method.from_reader) but with the given [`ParseOptions`].
+    pub fn from_reader_with<R: Read>(reader: R, options: &ParseOptions) -> Result<Report, Error> {
+        let mut reader = Reader::from_reader(BufReader::new(reader));
+        match read_report(&mut reader, options)? {
+            Some(report) => Ok(report),
+            None => Err(Error::Parse("no root element found".into())),
+        }
+    }
+
+    /// Read all `*.xml` files in `dir` into a single Report, e.g. the `TEST-*.xml`
+    /// files of a `surefire-reports` directory.
+    ///
+    /// The files are read in the order of their names and the test suites of all
+    /// files are added to the Report. Other files and subdirectories are ignored.
+    pub fn from_dir<P: AsRef<Path>>(dir: P) -> Result<Report, Error> {
+        Report::from_dir_with(dir, &ParseOptions::default())
+    }
+
+    /// Same as [`from_dir`](#method.from_dir) but with the given [`ParseOptions`].
+    ///
+    /// A file that cannot be parsed fails with an [`Error::Parse`] naming the file,
+    /// unless [`ParseOptions::skip_invalid_files`] is set.
+    pub fn from_dir_with<P: AsRef<Path>>(dir: P, options: &ParseOptions) -> Result<Report, Error> {
+        let mut paths = Vec::new();
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.is_file() && path.extension().is_some_and(|e| e == "xml") {
+                paths.push(path);
+            }
+        }
+        paths.sort();
+
+        let mut report = Report::new();
+        for path in paths {
+            match Report::from_reader_with(File::open(&path)?, options) {
+                Ok(r) => report.add_testsuites(r.testsuites),
+                Err(_) if options.skip_invalid_files => {}
+                Err(e) => {
+                    return Err(Error::Parse(format!("{}: {}", path.display(), e)));
+                }
+            }
+        }
+        Ok(report)
+    }
+
+    /// Read all Reports from a stream of concatenated JUnit XML documents.
+    ///
+    /// Some tools append to an existing report file instead of replacing it,
+    /// which results in several `<?xml ...?>` declarations and root elements
+    /// in a single file. Every root element is returned as its own `Report`.
+    pub fn parse_multi<R: Read>(reader: R) -> Result<Vec<Report>, Error> {
+        let mut reader = Reader::from_reader(BufReader::new(reader));
+        let mut reports = Vec::new();
+        while let Some(report) = read_report(&mut reader, &ParseOptions::default())? {
+            reports.push(report);
+        }
+        Ok(reports)
+    }
+}
+
+impl FromStr for Report {
+    type Err = Error;
+
+    /// Parse a Report from JUnit XML, see [`Report::from_reader`].
+    fn from_str(xml: &str) -> Result<Report, Error> {
+        Report::from_reader(xml.as_bytes())
+    }
+}
+
+/// Read the next root element, returns `None` if the end of the input was reached.
+fn read_report<R: BufRead>(
+    reader: &mut Reader<R>,
+    options: &ParseOptions,
+) -> Result<Option<Report>, Error> {
+    loop {
+        match read_event(reader)? {
+            Event::Start(e) if e.name().as_ref() == b"testsuites" => {
+                let mut report = read_root(&e)?;
+                loop {
+                    match read_event(reader)? {
+                        Event::Start(e) => match e.name().as_ref() {
+                            b"testsuite" => {
+                                report.add_testsuite(read_testsuite(reader, &e, false, options)?)
+                            }
+                            b"properties" => report.properties.extend(read_properties(reader)?),
+                            b"system-out" => report.system_out = Some(read_text(reader)?),
+                            b"system-err" => report.system_err = Some(read_text(reader)?),
+                            _ => skip(reader, &e)?,
+                        },
+                        Event::Empty(e) => match e.name().as_ref() {
+                            b"testsuite" => {
+                                report.add_testsuite(read_testsuite(reader, &e, true, options)?)
+                            }
+                            b"system-out" => report.system_out = Some(String::new()),
+                            b"system-err" => report.system_err = Some(String::new()),
+                            _ => {}
+                        },
+                        Event::End(_) => return Ok(Some(report)),
+                        Event::Eof => return Err(unexpected_eof()),
+                        _ => {}
+                    }
+                }
+            }
+            Event::Empty(e) if e.name().as_ref() == b"testsuites" => {
+                return Ok(Some(read_root(&e)?));
+            }
+            Event::Start(e) if e.name().as_ref() == b"testsuite" => {
+                let mut report = Report::new();
+                report.add_testsuite(read_testsuite(reader, &e, false, options)?);
+                return Ok(Some(report));
+            }
+            Event::Empty(e) if e.name().as_ref() == b"testsuite" => {
+                let mut report = Report::new();
+                report.add_testsuite(read_testsuite(reader, &e, true, options)?);
+                return Ok(Some(report));
+            }
+            Event::Start(e) | Event::Empty(e) => {
+                return Err(Error::Parse(format!(
+                    "unexpected root element `{}`",
+                    String::from_utf8_lossy(e.name().as_ref())
+                )));
+            }
+            Event::Eof => return Ok(None),
+            _ => {}
+        }
+    }
+}
+
+/// Create a Report from the attributes of the `<testsuites>` element `start`.
+///
+/// The aggregated counts and time are computed from the test suites, all other
+/// attributes are kept as extra attributes.
+fn read_root(start: &BytesStart) -> Result<Report, Error> {
+    let mut report = Report::new();
+    for attr in start.attributes() {
+        let attr = attr.map_err(quick_xml::Error::from)?;
+        match attr.key.as_ref() {
+            b"tests" | b"failures" | b"errors" | b"skipped" | b"disabled" | b"time" => {}
+            b"name" => report.name = Some(attr.unescape_value()?.into_owned()),
+            key if !is_name(key) => {}
+            key => {
+                report.set_extra_attribute(&String::from_utf8_lossy(key), &attr.unescape_value()?)
+            }
+        }
+    }
+    Ok(report)
+}
+
+/// Check that `key` is a valid XML attribute name.
+///
+/// Malformed start tags can produce garbage keys, writing them back would
+/// result in invalid XML.
+fn is_name(key: &[u8]) -> bool {
+    let start = |c: &u8| c.is_ascii_alphabetic() || *c == b'_' || *c == b':' || !c.is_ascii();
+    match key.split_first() {
+        Some((first, rest)) => {
+            start(first)
+                && rest
+                    .iter()
+                    .all(|c| start(c) || c.is_ascii_digit() || *c == b'-' || *c == b'.')
+        }
+        None => false,
+    }
+}
+
+/// Read a `<testsuite>` element whose start tag is `start`.
+///
+/// A `time` attribute that differs from the sum of the test case times is kept as
+/// [`TestSuite::set_time`](struct.TestSuite.html#method.set_time) would set it.
+fn read_testsuite<R: BufRead>(
+    reader: &mut Reader<R>,
+    start: &BytesStart,
+    empty: bool,
+    options: &ParseOptions,
+) -> Result<TestSuite, Error> {
+    let mut ts = TestSuite::new("");
+    ts.timestamp = None;
+    let mut package = None;
+    let mut time = None;
+    for attr in start.attributes() {
+        let attr = attr.map_err(quick_xml::Error::from)?;
+        let value = attr.unescape_value()?;
+        match attr.key.as_ref() {
+            b"name" => ts.name = value.into_owned(),
+            b"package" => package = Some(value.into_owned()),
+            b"hostname" => ts.hostname = value.into_owned(),
+            b"file" => ts.filepath = Some(value.into_owned()),
+            b"timestamp" => ts.timestamp = Some(parse_timestamp(&value, options)?),
+            b"time" => {
+                parse_time(&value, options.time_unit)?;
+                time = Some(value.into_owned());
+            }
+            _ => {}
+        }
+    }
+    ts.package = package.unwrap_or_else(|| format!("testsuite/{}", ts.name));
+
+    if !empty {
+        read_testsuite_content(reader, &mut ts, options)?;
+    }
+    if let Some(time) = time.filter(|time| !is_time_of(time, ts.time(), options.time_unit)) {
+        ts.set_time(parse_time(&time, options.time_unit)?);
+    }
+    Ok(ts)
+}
+
+/// Read the children of a `<testsuite>` element into `ts`.
+fn read_testsuite_content<R: BufRead>(
+    reader: &mut Reader<R>,
+    ts: &mut TestSuite,
+    options: &ParseOptions,
+) -> Result<(), Error> {
+    loop {
+        match read_event(reader)? {
+            Event::Start(e) => match e.name().as_ref() {
+                b"properties" => ts.properties.extend(read_properties(reader)?),
+                b"testcase" => ts.add_testcase(read_testcase(reader, &e, false, options)?),
+                b"system-out" => ts.system_out.push(read_text(reader)?),
+                b"system-err" => ts.system_err.push(read_text(reader)?),
+                _ => skip(reader, &e)?,
+            },
+            Event::Empty(e) => match e.name().as_ref() {
+                b"testcase" => ts.add_testcase(read_testcase(reader, &e, true, options)?),
+                b"system-out" => ts.system_out.push(String::new()),
+                b"system-err" => ts.system_err.push(String::new()),
+                _ => {}
+            },
+            Event::End(_) => return Ok(()),
+            Event::Eof => return Err(unexpected_eof()),
+            _ => {}
+        }
+    }
+}
+
+/// Check if the `time` attribute `value` is `time` written with the number of decimal
+/// places of `value`, i.e. equal up to the rounding of the writer.
+fn is_time_of(value: &str, time: Duration, time_unit: TimeUnit) -> bool {
+    let value = value.trim();
+    let places = value
+        .split_once('.')
+        .map_or(0, |(_, fraction)| fraction.len());
+    let time = match time_unit {
+        TimeUnit::Seconds => time.as_seconds_f64(),
+        TimeUnit::Milliseconds => time.whole_nanoseconds() as f64 / 1_000_000.0,
+    };
+    format_seconds(time, Some(places)).parse::<f64>().ok() == value.parse::<f64>().ok()
+}
+
+/// Read the `<property>` elements of a `<properties>` element.
+fn read_properties<R: BufRead>(reader: &mut Reader<R>) -> Result<Vec<Property>, Error> {
+    let mut properties = Vec::new();
+    loop {
+        match read_event(reader)? {
+            Event::Start(e) | Event::Empty(e) if e.name().as_ref() == b"property" => {
+                let mut property = Property::new("", "");
+                for attr in e.attributes() {
+                    let attr = attr.map_err(quick_xml::Error::from)?;
+                    match attr.key.as_ref() {
+                        b"name" => property.name = attr.unescape_value()?.into_owned(),
+                        b"value" => property.value = attr.unescape_value()?.into_owned(),
+                        _ => {}
+                    }
+                }
+                properties.push(property);
+            }
+            Event::Start(e) => skip(reader, &e)?,
+            Event::End(e) if e.name().as_ref() == b"properties" => return Ok(properties),
+            Event::Eof => return Err(unexpected_eof()),
+            _ => {}
+        }
+    }
+}
+
+/// Read a `<testcase>` element whose start tag is `start`.
+fn read_testcase<R: BufRead>(
+    reader: &mut Reader<R>,
+    start: &BytesStart,
+    empty: bool,
+    options: &ParseOptions,
+) -> Result<TestCase, Error> {
+    let mut tc = TestCase::success("", Duration::ZERO);
+    for attr in start.attributes() {
+        let attr = attr.map_err(quick_xml::Error::from)?;
+        let value = attr.unescape_value()?;
+        match attr.key.as_ref() {
+            b"name" => tc.name = value.into_owned(),
+            b"time" => tc.time = parse_time(&value, options.time_unit)?,
+            b"assertions" => tc.assertions = Some(parse_number(&value, "assertions")?),
+            b"classname" => tc.classname = Some(value.into_owned()),
+            b"file" => tc.filepath = Some(value.into_owned()),
+            b"line" => tc.line = Some(parse_number(&value, "line")?),
+            b"hostname" => tc.hostname = Some(value.into_owned()),
+            b"group" => tc.group = Some(value.into_owned()),
+            b"timestamp" => tc.timestamp = Some(parse_timestamp(&value, options)?),
+            _ => {}
+        }
+    }
+
+    if empty {
+        return Ok(tc);
+    }
+
+    loop {
+        match read_event(reader)? {
+            Event::Start(e) => match e.name().as_ref() {
+                b"error" | b"failure" => {
+                    let cause = Some(read_text(reader)?).filter(|c| !c.is_empty());
+                    add_result(&mut tc, read_result(&e, cause)?);
+                }
+                b"skipped" => {
+                    skip(reader, &e)?;
+                    tc.result = read_skipped(&e)?;
+                    tc.additional_results.clear();
+                }
+                b"properties" => tc.properties.extend(read_properties(reader)?),
+                b"system-out" => tc.system_out.push(read_text(reader)?),
+                b"system-err" => tc.system_err.push(read_text(reader)?),
+                _ => skip(reader, &e)?,
+            },
+            Event::Empty(e) => match e.name().as_ref() {
+                b"error" | b"failure" => add_result(&mut tc, read_result(&e, None)?),
+                b"skipped" => {
+                    tc.result = read_skipped(&e)?;
+                    tc.additional_results.clear();
+                }
+                b"system-out" => tc.system_out.push(String::new()),
+                b"system-err" => tc.system_err.push(String::new()),
+                _ => {}
+            },
+            Event::End(_) => return Ok(tc),
+            Event::Eof => return Err(unexpected_eof()),
+            _ => {}
+        }
+    }
+}
+
+/// Add the result of an `<error>` or `<failure>` element, the first one becomes the
+/// `result` of `tc`.
+fn add_result(tc: &mut TestCase, result: TestResult) {
+    if tc.is_problem() {
+        tc.additional_results.push(result);
+    } else {
+        tc.result = result;
+    }
+}
+
+/// Create the [`TestResult`] for an `<error>` or `<failure>` element.
+fn read_result(start: &BytesStart, cause: Option<String>) -> Result<TestResult, Error> {
+    let mut type_ = String::new();
+    let mut message = String::new();
+    let mut file = None;
+    let mut line = None;
+    for attr in start.attributes() {
+        let attr = attr.map_err(quick_xml::Error::from)?;
+        match attr.key.as_ref() {
+            b"type" => type_ = attr.unescape_value()?.into_owned(),
+            b"message" => message = attr.unescape_value()?.into_owned(),
+            b"file" => file = Some(attr.unescape_value()?.into_owned()),
+            b"line" => line = Some(parse_number(&attr.unescape_value()?, "line")?),
+            _ => {}
+        }
+    }
+    Ok(match start.name().as_ref() {
+        b"error" => TestResult::Error {
+            type_,
+            message,
+            cause,
+            file,
+            line,
+        },
+        _ => TestResult::Failure {
+            type_,
+            message,
+            cause,
+            file,
+            line,
+        },
+    })
+}
+
+/// Create the [`TestResult`] for a `<skipped>` element, `type="disabled"` marks
+/// a disabled test case.
+fn read_skipped(start: &BytesStart) -> Result<TestResult, Error> {
+    let mut message = None;
+    for attr in start.attributes() {
+        let attr = attr.map_err(quick_xml::Error::from)?;
+        match attr.key.as_ref() {
+            b"type" if attr.unescape_value()? == "disabled" => return Ok(TestResult::Disabled),
+            b"message" => message = Some(attr.unescape_value()?.into_owned()),
+            _ => {}
+        }
+    }
+    Ok(TestResult::Skipped { message })
+}
+
+/// Read the text and CDATA content of the current element up to its end tag.
+///
+/// Text consisting only of whitespace, e.g. indentation, is ignored.
+fn read_text<R: BufRead>(reader: &mut Reader<R>) -> Result<String, Error> {
+    let mut text = String::new();
+    loop {
+        match read_event(reader)? {
+            Event::Text(e) => {
+                let e = e.unescape()?;
+                if !e.trim().is_empty() {
+                    text.push_str(&e);
+                }
+            }
+            Event::CData(e) => text.push_str(&reader.decoder().decode(&e)?),
+            Event::Start(e) => skip(reader, &e)?,
+            Event::End(_) => return Ok(text),
+            Event::Eof => return Err(unexpected_eof()),
+            _ => {}
+        }
+    }
+}
+
+/// Read the next event, detached from the internal buffer.
+fn read_event<R: BufRead>(reader: &mut Reader<R>) -> Result<Event<'static>, Error> {
+    let mut buf = Vec::new();
+    Ok(reader.read_event_into(&mut buf)?.into_owned())
+}
+
+/// Skip the element whose start tag is `start`, including all its children.
+fn skip<R: BufRead>(reader: &mut Reader<R>, start: &BytesStart) -> Result<(), Error> {
+    let mut buf = Vec::new();
+    reader.read_to_end_into(start.name(), &mut buf)?;
+    Ok(())
+}
+
+/// Parse a `time` attribute given in `time_unit`.
+fn parse_time(value: &str, time_unit: TimeUnit) -> Result<Duration, Error> {
+    value
+        .trim()
+        .parse::<f64>()
+        .ok()
+        .map(|time| match time_unit {
+            TimeUnit::Seconds => time,
+            TimeUnit::Milliseconds => time / 1000.0,
+        })
+        .and_then(Duration::checked_seconds_f64)
+        .ok_or_else(|| Error::Parse(format!("invalid time `{}`", value)))
+}
+
+/// Parse a numeric attribute like `line`.
+fn parse_number(value: &str, attribute: &str) -> Result<u64, Error> {
+    value
+        .trim()
+        .parse()
+        .map_err(|_| Error::Parse(format!("invalid {} `{}`", attribute, value)))
+}
+
+/// Parse a RFC 3339 `timestamp` attribute, preserving its offset.
+///
+/// With [`ParseOptions::lenient_timestamps`] a space may separate date and time and
+/// timestamps without offset are assumed to be in UTC.
+fn parse_timestamp(value: &str, options: &ParseOptions) -> Result<OffsetDateTime, Error> {
+    let value = value.trim();
+    OffsetDateTime::parse(value, &Rfc3339)
+        .or_else(|e| {
+            if !options.lenient_timestamps {
+                return Err(e);
+            }
+            let value = value.replacen(' ', "T", 1);
+            OffsetDateTime::parse(&value, &Rfc3339).or_else(|_| {
+                PrimitiveDateTime::parse(
+                    &value,
+                    format_description!(
+                        "[year]-[month]-[day]T[hour]:[minute]:[second][optional [.[subsecond]]]"
+                    ),
+                )
+                .map(PrimitiveDateTime::assume_utc)
+            })
+        })
+        .map_err(|e| Error::Parse(format!("invalid timestamp `{}`: {}", value, e)))
+}
+
+/// Error for input that ends inside of an element.
+fn unexpected_eof() -> Error {
+    Error::Parse("unexpected end of file".into())
+}
@@ -5,32 +5,122 @@
  * SPDX-License-Identifier:     MIT
  */
 
-use std::io::Write;
+use std::borrow::Cow;
+use std::cell::{Cell, RefCell};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::ffi::OsString;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::ops::AddAssign;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering};
 
-use derive_getters::Getters;
-use quick_xml::events::BytesDecl;
+use quick_xml::events::{BytesDecl, BytesEnd, BytesStart};
 use quick_xml::{
-    events::{BytesCData, Event},
-    ElementWriter, Result, Writer,
+    events::{BytesCData, BytesText, Event},
+    ElementWriter, Writer,
+};
+use time::{
+    format_description::well_known::Rfc3339, macros::format_description, Duration, OffsetDateTime,
 };
-use time::format_description::well_known::Rfc3339;
 
-use crate::{TestCase, TestResult, TestSuite};
+use crate::collections::add_assertions;
+use crate::{
+    Dialect, Error, LineEnding, Property, ReportOptions, Status, Summary, TestCase, TestCaseTime,
+    TestResult, TestSuite, TextMode, TimeUnit, WriteWarning,
+};
 
 /// Root element of a JUnit report
-#[derive(Default, Debug, Clone, Getters)]
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
 pub struct Report {
-    testsuites: Vec<TestSuite>,
+    pub(crate) name: Option<String>,
+    pub(crate) testsuites: Vec<TestSuite>,
+    pub(crate) properties: Vec<Property>,
+    pub(crate) system_out: Option<String>,
+    pub(crate) system_err: Option<String>,
+    pub(crate) extra_attributes: BTreeMap<String, String>,
 }
 
 impl Report {
+    /// Name of the report, written as `name` attribute of `<testsuites>`
+    pub fn name(&self) -> &Option<String> {
+        &self.name
+    }
+
+    /// Test suites of the report
+    pub fn testsuites(&self) -> &Vec<TestSuite> {
+        &self.testsuites
+    }
+
+    /// Report-level properties
+    pub fn properties(&self) -> &Vec<Property> {
+        &self.properties
+    }
+
+    /// Report-level `<system-out>` content, if set
+    pub fn system_out(&self) -> &Option<String> {
+        &self.system_out
+    }
+
+    /// Report-level `<system-err>` content, if set
+    pub fn system_err(&self) -> &Option<String> {
+        &self.system_err
+    }
+
+    /// Additional attributes of the root `<testsuites>` element, see [`set_extra_attribute`](#method.set_extra_attribute)
+    pub fn extra_attributes(&self) -> &BTreeMap<String, String> {
+        &self.extra_attributes
+    }
+
     /// Create a new empty Report
     pub fn new() -> Report {
         Report {
+            name: None,
             testsuites: Vec::new(),
+            properties: Vec::new(),
+            system_out: None,
+            system_err: None,
+            extra_attributes: BTreeMap::new(),
         }
     }
 
+    /// Set the name of the Report.
+    ///
+    /// A named report is written with the aggregated `tests`, `failures`, `errors` and
+    /// `time` attributes on the root `<testsuites>` element, as with
+    /// [`ReportOptions::root_totals`].
+    pub fn set_name(&mut self, name: &str) {
+        self.name = Some(name.to_owned());
+    }
+
+    /// Set an additional attribute of the root `<testsuites>` element.
+    ///
+    /// Extra attributes are written after all other attributes, ordered by name.
+    /// The parser stores root attributes it does not compute itself here, so
+    /// e.g. a `package` attribute of legacy Ant reports survives a round trip.
+    pub fn set_extra_attribute(&mut self, name: &str, value: &str) {
+        self.extra_attributes
+            .insert(name.to_owned(), value.to_owned());
+    }
+
+    /// Add a report-level [`Property`](struct.Property.html) to the Report.
+    ///
+    /// Properties are written before the test suites in the order they were added.
+    pub fn add_property(&mut self, name: &str, value: &str) {
+        self.properties.push(Property::new(name, value));
+    }
+
+    /// Set the report-level `system_out`, written after the test suites.
+    pub fn set_system_out(&mut self, system_out: &str) {
+        self.system_out = Some(system_out.to_owned());
+    }
+
+    /// Set the report-level `system_err`, written after the test suites.
+    pub fn set_system_err(&mut self, system_err: &str) {
+        self.system_err = Some(system_err.to_owned());
+    }
+
     /// Add a [`TestSuite`](struct.TestSuite.html) to this report.
     ///
     /// The function takes ownership of the supplied [`TestSuite`](struct.TestSuite.html).
@@ -43,156 +133,1037 @@ impl Report {
         self.testsuites.extend(testsuites);
     }
 
+    /// Append the test suites of `other` to this report, e.g. to combine the reports
+    /// of parallel test shards.
+    ///
+    /// The suites are renumbered when the report is written. The name, properties,
+    /// output and extra attributes of `other` are discarded.
+    pub fn merge(&mut self, other: Report) {
+        self.testsuites.extend(other.testsuites);
+    }
+
+    /// Combine several reports into one, see [`merge`](#method.merge).
+    ///
+    /// The report-level fields of the first report are kept, an empty report is
+    /// returned if there is none.
+    ///
+    /// ```rust
+    /// use junit_report::{Report, TestSuite};
+    ///
+    /// let shards = ["shard1", "shard2"].map(|name| {
+    ///     let mut r = Report::new();
+    ///     r.add_testsuite(TestSuite::new(name));
+    ///     r
+    /// });
+    /// let r = Report::merge_all(shards);
+    /// assert_eq!(r.testsuites().len(), 2);
+    /// ```
+    #[must_use]
+    pub fn merge_all(reports: impl IntoIterator<Item = Report>) -> Report {
+        let mut reports = reports.into_iter();
+        let mut report = reports.next().unwrap_or_default();
+        for other in reports {
+            report.merge(other);
+        }
+        report
+    }
+
+    /// Remove all test suites from the Report.
+    pub fn clear(&mut self) {
+        self.testsuites.clear();
+    }
+
+    /// Return the `n` slowest test cases of all test suites together with their suite.
+    ///
+    /// The test cases are ordered by decreasing duration, test cases with the same
+    /// duration are ordered by name.
+    #[must_use]
+    pub fn slowest(&self, n: usize) -> Vec<(&TestSuite, &TestCase)> {
+        let mut cases: Vec<_> = self
+            .testsuites
+            .iter()
+            .flat_map(|ts| ts.testcases.iter().map(move |tc| (ts, tc)))
+            .collect();
+        cases.sort_by(|(_, a), (_, b)| b.time.cmp(&a.time).then_with(|| a.name.cmp(&b.name)));
+        cases.truncate(n);
+        cases
+    }
+
+    /// Check if the Report contains no test suites at all.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.testsuites.is_empty()
+    }
+
+    /// Check if at least one test suite of the Report contains a test case.
+    ///
+    /// A Report with only empty test suites is not [`is_empty`](#method.is_empty)
+    /// but has no tests either.
+    #[must_use]
+    pub fn has_tests(&self) -> bool {
+        self.testsuites.iter().any(|ts| !ts.testcases.is_empty())
+    }
+
+    /// Return a copy of the Report containing only the test suites matching `f`.
+    ///
+    /// Report-level properties, output and attributes are kept. The `id` of the test
+    /// suites is their position in the new Report.
+    #[must_use]
+    pub fn filter<F: Fn(&TestSuite) -> bool>(&self, f: F) -> Report {
+        Report {
+            name: self.name.clone(),
+            testsuites: self.testsuites.iter().filter(|ts| f(ts)).cloned().collect(),
+            properties: self.properties.clone(),
+            system_out: self.system_out.clone(),
+            system_err: self.system_err.clone(),
+            extra_attributes: self.extra_attributes.clone(),
+        }
+    }
+
+    /// Remove all successful and skipped test cases from every test suite.
+    ///
+    /// Only erroneous and failed test cases remain, which gives a compact report for
+    /// alerting. If `remove_empty_suites` is set, test suites without any remaining test
+    /// case are removed as well. The counts and times written afterwards reflect the
+    /// filtered test cases.
+    pub fn retain_problems(&mut self, remove_empty_suites: bool) {
+        for ts in &mut self.testsuites {
+            ts.testcases.retain(TestCase::is_problem);
+        }
+        if remove_empty_suites {
+            self.testsuites.retain(|ts| !ts.testcases.is_empty());
+        }
+    }
+
+    /// Check if any test case of the Report is erroneous or failed.
+    #[must_use]
+    pub fn has_problems(&self) -> bool {
+        self.testsuites.iter().any(TestSuite::has_problems)
+    }
+
+    /// Count the erroneous and failed test cases of the Report.
+    #[must_use]
+    pub fn num_problems(&self) -> usize {
+        self.testsuites
+            .iter()
+            .map(|ts| ts.errors() + ts.failures())
+            .sum()
+    }
+
+    /// Exit code for a process reporting the Report, `0` without problems and `1` otherwise.
+    ///
+    /// This allows CLI wrappers to end with `std::process::exit(report.exit_code())`.
+    #[must_use]
+    pub fn exit_code(&self) -> i32 {
+        if self.has_problems() {
+            1
+        } else {
+            0
+        }
+    }
+
+    /// Compute the [`Summary`](struct.Summary.html) over all test suites of the Report.
+    #[must_use]
+    pub fn summary(&self) -> Summary {
+        self.testsuites.iter().map(TestSuite::summary).fold(
+            Summary::default(),
+            |mut summary, ts| {
+                summary.tests += ts.tests;
+                summary.errors += ts.errors;
+                summary.failures += ts.failures;
+                summary.skipped += ts.skipped;
+                summary.disabled += ts.disabled;
+                summary.assertions = add_assertions(summary.assertions, ts.assertions);
+                summary.time = summary.time.saturating_add(ts.time);
+                summary
+            },
+        )
+    }
+
+    /// Return the name and [`Summary`](struct.Summary.html) of every test suite in order.
+    #[must_use]
+    pub fn stats_by_suite(&self) -> Vec<(String, Summary)> {
+        self.testsuites
+            .iter()
+            .map(|ts| (ts.name.clone(), ts.summary()))
+            .collect()
+    }
+
+    /// Sort the test cases of every test suite by the key extracted with `f`.
+    ///
+    /// The sort is stable, test cases with equal keys keep their order. The test
+    /// suites themselves are not reordered.
+    pub fn sort_testcases_by<F, K>(&mut self, mut f: F)
+    where
+        F: FnMut(&TestCase) -> K,
+        K: Ord,
+    {
+        for ts in &mut self.testsuites {
+            ts.testcases.sort_by_key(&mut f);
+        }
+    }
+
+    /// Replace every duration of the report by the result of `f`.
+    ///
+    /// `f` is applied to the `time` of every test case and to the explicit time and
+    /// additional time of every test suite, see [`TestSuite::set_time`] and
+    /// [`TestSuite::add_time`]. Suite times computed from the test cases follow
+    /// automatically. This allows e.g. to zero all times for golden file comparisons.
+    pub fn map_durations(&mut self, mut f: impl FnMut(Duration) -> Duration) {
+        for ts in &mut self.testsuites {
+            for tc in &mut ts.testcases {
+                tc.time = f(tc.time);
+            }
+            ts.time_override = ts.time_override.map(&mut f);
+            ts.extra_time = f(ts.extra_time);
+        }
+    }
+
+    /// Estimate the size in bytes of the XML written by [`write_xml`](#method.write_xml)
+    /// without writing it.
+    ///
+    /// The estimate is the length of all names, messages and output plus a fixed
+    /// overhead per element. It ignores escaping and [`ReportOptions`], for reports
+    /// written with the default options it is within a factor of two of the actual size.
+    /// Adding test suites, test cases or any content never decreases the estimate.
+    #[must_use]
+    pub fn estimated_xml_size(&self) -> usize {
+        60 + self.name.as_ref().map_or(0, |n| 80 + n.len())
+            + estimated_properties_size(&self.properties)
+            + self
+                .testsuites
+                .iter()
+                .map(TestSuite::estimated_xml_size)
+                .sum::<usize>()
+            + estimated_output_size(self.system_out.iter())
+            + estimated_output_size(self.system_err.iter())
+    }
+
+    /// Create a new Report from `(suite name, TestCase)` pairs.
+    ///
+    /// All test cases sharing a suite name end up in the same [`TestSuite`](struct.TestSuite.html),
+    /// even if they are not adjacent in the input. Suites are ordered by the first
+    /// occurrence of their name and the test cases keep their relative order.
+    pub fn from_grouped(iter: impl IntoIterator<Item = (String, TestCase)>) -> Report {
+        let mut report = Report::new();
+        let mut index: HashMap<String, usize> = HashMap::new();
+
+        for (name, testcase) in iter {
+            let id = *index.entry(name).or_insert_with_key(|name| {
+                report.testsuites.push(TestSuite::new(name));
+                report.testsuites.len() - 1
+            });
+            report.testsuites[id].add_testcase(testcase);
+        }
+
+        report
+    }
+
     /// Write the XML version of the Report to the given `Writer`.
-    pub fn write_xml<W: Write>(&self, sink: W) -> Result<()> {
-        let mut writer = Writer::new(sink);
+    ///
+    /// Errors that happen while writing a [`TestSuite`](struct.TestSuite.html) are
+    /// reported as [`Error::Write`] with the position of the suite and test case.
+    pub fn write_xml<W: Write>(&self, sink: W) -> Result<(), Error> {
+        self.write_xml_with(sink, &ReportOptions::default())
+    }
 
-        writer.write_event(Event::Decl(BytesDecl::new("1.0", Some("utf-8"), None)))?;
+    /// Write the XML version of the Report to the given `Writer` and return it.
+    ///
+    /// This allows to pass owned sinks, e.g. a `Vec<u8>`, and inspect them afterwards.
+    pub fn write_xml_return<W: Write>(&self, sink: W) -> Result<W, Error> {
+        self.write_xml_into(sink, &ReportOptions::default())
+            .map(|(sink, _)| sink)
+    }
+
+    /// Write the XML version of the Report indented with two spaces per level.
+    ///
+    /// This is a shortcut for [`write_xml_with`](#method.write_xml_with) with
+    /// [`ReportOptions::indent`] set to `Some((b' ', 2))`.
+    pub fn write_xml_pretty<W: Write>(&self, sink: W) -> Result<(), Error> {
+        let options = ReportOptions {
+            indent: Some((b' ', 2)),
+            ..ReportOptions::default()
+        };
+        self.write_xml_with(sink, &options)
+    }
+
+    /// Write the XML version of the Report on a single line.
+    ///
+    /// This is the same as [`write_xml`](#method.write_xml), which is compact by default.
+    pub fn write_xml_compact<W: Write>(&self, sink: W) -> Result<(), Error> {
+        self.write_xml(sink)
+    }
+
+    /// Return the XML version of the Report as `String`.
+    pub fn to_xml_string(&self) -> Result<String, Error> {
+        let xml = self.write_xml_return(Vec::new())?;
+        String::from_utf8(xml).map_err(|e| Error::Io(io::Error::new(io::ErrorKind::InvalidData, e)))
+    }
+
+    /// Write the XML version of the Report to the given `Writer` using the given
+    /// [`ReportOptions`](struct.ReportOptions.html).
+    pub fn write_xml_with<W: Write>(&self, sink: W, options: &ReportOptions) -> Result<(), Error> {
+        self.write_xml_into(sink, options).map(drop)
+    }
+
+    /// Same as [`write_xml_with`](#method.write_xml_with) but returns the non-fatal
+    /// adjustments made to the content in order to write a valid report.
+    ///
+    /// Negative times are written as zero, times are clamped to [`ReportOptions::max_time`]
+    /// and characters that are not allowed in XML 1.0 are removed, which
+    /// [`write_xml_with`](#method.write_xml_with) does silently. Only content that is
+    /// actually written is taken into account, e.g. output omitted by the
+    /// [`Dialect`](enum.Dialect.html) does not cause a warning. Every affected test suite,
+    /// test case or the report itself is reported once per kind of adjustment, ordered
+    /// by their position in the report.
+    pub fn write_xml_with_warnings<W: Write>(
+        &self,
+        sink: W,
+        options: &ReportOptions,
+    ) -> Result<Vec<WriteWarning>, Error> {
+        self.write_xml_into(sink, options)
+            .map(|(_, warnings)| warnings)
+    }
+
+    /// Write the Report as a single `<testsuites>` element without XML declaration.
+    ///
+    /// The output is still a complete element that can be embedded in a larger
+    /// document, e.g. an HTML `<script type="application/xml">` element. It equals the
+    /// output of [`write_xml`](#method.write_xml) without the leading `<?xml ...?>`.
+    pub fn write_fragment<W: Write>(&self, sink: W) -> Result<(), Error> {
+        let options = ReportOptions {
+            omit_declaration: true,
+            ..ReportOptions::default()
+        };
+        self.write_xml_with(sink, &options)
+    }
+
+    /// Write the XML version of the Report to `sink` and return it together with the
+    /// adjustments made while writing.
+    fn write_xml_into<W: Write>(
+        &self,
+        sink: W,
+        options: &ReportOptions,
+    ) -> Result<(W, Vec<WriteWarning>), Error> {
+        if options.strict {
+            self.validate_required()?;
+        }
+
+        let stripped = Cell::new(0);
+        let warnings = Warnings::new(&stripped);
+        let sink = LineEndingWriter::new(
+            InvalidCharsWriter::new(sink, &stripped),
+            options.line_ending,
+        );
+        let writer = warnings.scope(None, None, || self.write_root(sink, options, &warnings))?;
+        Ok((writer.inner.inner, warnings.into_vec()))
+    }
+
+    /// Write the `<testsuites>` element, including the XML declaration, to `sink`.
+    fn write_root<W: Write>(
+        &self,
+        sink: W,
+        options: &ReportOptions,
+        warnings: &Warnings,
+    ) -> Result<W, Error> {
+        let mut writer = match options.indent {
+            Some((indent_char, indent_size)) => {
+                Writer::new_with_indent(sink, indent_char, indent_size)
+            }
+            None => Writer::new(sink),
+        };
+
+        if !options.omit_declaration {
+            writer.write_event(Event::Decl(BytesDecl::new("1.0", Some("utf-8"), None)))?;
+        }
+
+        let name = self.name.as_deref().or(options.root_name.as_deref());
+        let totals = (options.root_totals || name.is_some()).then(|| {
+            let summary = self.summary();
+            [
+                ("tests", summary.tests.to_string()),
+                ("failures", summary.failures.to_string()),
+                ("errors", summary.errors.to_string()),
+                ("time", format_time(summary.time, options, warnings)),
+            ]
+        });
 
         writer
             .create_element("testsuites")
+            .with_attributes(name.map(|name| ("name", name)))
+            .with_attributes(totals.iter().flatten().map(|(k, v)| (*k, v.as_str())))
+            .with_attributes(
+                self.extra_attributes
+                    .iter()
+                    .map(|(k, v)| (k.as_str(), v.as_str())),
+            )
+            .write_empty_or_inner(
+                options,
+                |_| {
+                    self.testsuites.is_empty()
+                        && self.properties.is_empty()
+                        && output(&self.system_out, options).is_empty()
+                        && output(&self.system_err, options).is_empty()
+                },
+                |w| {
+                    w.write_properties(&self.properties, options)?
+                        .write_iter(self.testsuites.iter().enumerate(), |w, (id, ts)| {
+                            warnings
+                                .scope(Some(id), None, || ts.write_xml(w, id, options, warnings))
+                                .map_err(|e| e.at(id, None))
+                        })?
+                        .write_iter(output(&self.system_out, options), |writer, out| {
+                            writer
+                                .create_element("system-out")
+                                .write_text_content_with(&out, options)
+                                .map_err(Error::from)
+                        })?
+                        .write_iter(output(&self.system_err, options), |writer, err| {
+                            writer
+                                .create_element("system-err")
+                                .write_text_content_with(&err, options)
+                                .map_err(Error::from)
+                        })
+                        .map(drop)
+                },
+            )?;
+
+        Ok(writer.into_inner())
+    }
+
+    /// Start writing a report piece by piece to `sink`.
+    ///
+    /// Together with [`write_suite`](#method.write_suite) and
+    /// [`write_footer`](#method.write_footer) this allows to write test suites as they
+    /// become available, e.g. to several sinks at once. `write_header` has to be called
+    /// once, followed by any number of `write_suite` calls and a single `write_footer`
+    /// call that completes the document. The output matches [`write_xml`](#method.write_xml)
+    /// of a report with the same test suites and no report-level properties or output,
+    /// except that the root element is never written as empty element.
+    pub fn write_header<W: Write>(sink: W) -> Result<Writer<W>, Error> {
+        let mut writer = Writer::new(sink);
+        writer.write_event(Event::Decl(BytesDecl::new("1.0", Some("utf-8"), None)))?;
+        writer.write_event(Event::Start(BytesStart::new("testsuites")))?;
+        Ok(writer)
+    }
+
+    /// Write `testsuite` with the given `id` to a writer returned by
+    /// [`write_header`](#method.write_header).
+    ///
+    /// The `id` is the position of the suite in the report, starting at 0. The suite is
+    /// validated, sanitized and written according to `options` like by
+    /// [`write_xml_with`](#method.write_xml_with), except that
+    /// [`ReportOptions::indent`] and [`ReportOptions::omit_declaration`] are ignored as
+    /// the header is always written without indentation and with declaration.
+    pub fn write_suite<W: Write>(
+        writer: &mut Writer<W>,
+        testsuite: &TestSuite,
+        id: usize,
+        options: &ReportOptions,
+    ) -> Result<(), Error> {
+        if options.strict {
+            testsuite.validate_required(id)?;
+        }
+
+        let stripped = Cell::new(0);
+        let warnings = Warnings::new(&stripped);
+        let mut suite_writer = Writer::new(LineEndingWriter::new(
+            InvalidCharsWriter::new(Vec::new(), &stripped),
+            options.line_ending,
+        ));
+        testsuite
+            .write_xml(&mut suite_writer, id, options, &warnings)
+            .map_err(|e| e.at(id, None))?;
+        writer
+            .get_mut()
+            .write_all(&suite_writer.into_inner().inner.inner)?;
+        Ok(())
+    }
+
+    /// Complete a report started with [`write_header`](#method.write_header) and return
+    /// the sink.
+    pub fn write_footer<W: Write>(mut writer: Writer<W>) -> Result<W, Error> {
+        writer.write_event(Event::End(BytesEnd::new("testsuites")))?;
+        Ok(writer.into_inner())
+    }
+
+    /// Check that all attributes required by the JUnit schema are non-empty.
+    fn validate_required(&self) -> Result<(), Error> {
+        for (id, ts) in self.testsuites.iter().enumerate() {
+            ts.validate_required(id)?;
+        }
+        Ok(())
+    }
+
+    /// Write the Report to the file at `path`, replacing its content.
+    ///
+    /// The file is created if it does not exist. See [`write_atomic`](#method.write_atomic)
+    /// if readers must never see a partially written file.
+    pub fn write_to_file<P: AsRef<Path>>(&self, path: P) -> Result<(), Error> {
+        let mut file = BufWriter::new(File::create(path)?);
+        self.write_xml(&mut file)?;
+        file.flush()?;
+        Ok(())
+    }
+
+    /// Write the Report to the file at `path` without readers ever seeing a partial file.
+    ///
+    /// The report is written to a temporary file in the same directory, which is renamed
+    /// to `path` once it is complete. An existing file at `path` is replaced. If writing
+    /// fails, the temporary file is removed and `path` is left untouched.
+    pub fn write_atomic<P: AsRef<Path>>(&self, path: P) -> Result<(), Error> {
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+        let path = path.as_ref();
+        let file_name = path.file_name().ok_or_else(|| {
+            Error::Io(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "path has no file name",
+            ))
+        })?;
+        let mut temp_name = OsString::from(".");
+        temp_name.push(file_name);
+        temp_name.push(format!(
+            ".{}-{}.tmp",
+            process::id(),
+            COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        let temp = path.with_file_name(temp_name);
+
+        let result = File::create(&temp).map_err(Error::from).and_then(|file| {
+            let mut file = BufWriter::new(file);
+            self.write_xml(&mut file)?;
+            file.into_inner()
+                .map_err(io::IntoInnerError::into_error)?
+                .sync_all()?;
+            fs::rename(&temp, path)?;
+            Ok(())
+        });
+        if result.is_err() {
+            let _ = fs::remove_file(&temp);
+        }
+        result
+    }
+
+    /// Write every [`TestSuite`](struct.TestSuite.html) into its own file in `dir`.
+    ///
+    /// Each file contains a complete report with a single test suite, which matches
+    /// the layout of a `surefire-reports` directory. The file name is returned by
+    /// `name_fn`, characters that are not valid in file names are replaced by `_`.
+    /// If two suites end up with the same file name, a numeric suffix is added to
+    /// the later one. Existing files are overwritten.
+    ///
+    /// Returns the paths of the written files in the order of the test suites.
+    pub fn write_split_to_dir<P: AsRef<Path>>(
+        &self,
+        dir: P,
+        name_fn: impl Fn(&TestSuite) -> String,
+    ) -> Result<Vec<PathBuf>, Error> {
+        let dir = dir.as_ref();
+        let mut used = HashSet::new();
+        let mut paths = Vec::with_capacity(self.testsuites.len());
+
+        for ts in &self.testsuites {
+            let name = sanitize_file_name(&name_fn(ts));
+            let (stem, extension) = match name.rfind('.') {
+                Some(i) if i > 0 => name.split_at(i),
+                _ => (name.as_str(), ""),
+            };
+            let mut file_name = name.clone();
+            let mut n = 1;
+            while !used.insert(file_name.clone()) {
+                file_name = format!("{}-{}{}", stem, n, extension);
+                n += 1;
+            }
+
+            let path = dir.join(file_name);
+            let mut report = Report::new();
+            report.add_testsuite(ts.clone());
+            let mut file = BufWriter::new(File::create(&path)?);
+            report.write_xml(&mut file)?;
+            file.flush()?;
+            paths.push(path);
+        }
+
+        Ok(paths)
+    }
+}
+
+/// Replace characters that are not allowed in file names on common platforms.
+fn sanitize_file_name(name: &str) -> String {
+    let name: String = name
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+    match name.as_str() {
+        "" | "." | ".." => "testsuite".to_string(),
+        _ => name,
+    }
+}
+
+impl TestSuite {
+    /// Check that all attributes required by the JUnit schema are non-empty.
+    fn validate_required(&self, id: usize) -> Result<(), Error> {
+        if self.name.is_empty() {
+            return Err(Error::Validation(format!("testsuite {} has no name", id)));
+        }
+        if self.properties.iter().any(|p| p.name.is_empty()) {
+            return Err(Error::Validation(format!(
+                "testsuite `{}` has a property without name",
+                self.name
+            )));
+        }
+        if let Some(i) = self.testcases.iter().position(|tc| tc.name.is_empty()) {
+            return Err(Error::Validation(format!(
+                "testcase {} of testsuite `{}` has no name",
+                i, self.name
+            )));
+        }
+        Ok(())
+    }
+
+    /// Write the XML version of the [`TestSuite`] with the given `id` to the given [`Writer`].
+    fn write_xml<'a, W: Write>(
+        &self,
+        w: &'a mut Writer<W>,
+        id: usize,
+        options: &ReportOptions,
+        warnings: &Warnings,
+    ) -> Result<&'a mut Writer<W>, Error> {
+        let id_attr = id.to_string();
+        let tests = self.tests().to_string();
+        let errors = self.errors().to_string();
+        let failures = self.failures().to_string();
+        let disabled = self.disabled();
+        // Other dialects write disabled test cases as skipped ones
+        let skipped = match options.dialect {
+            Dialect::Default => self.skipped(),
+            Dialect::Pytest | Dialect::Ant => self.skipped() + disabled,
+        }
+        .to_string();
+        let disabled_attr = disabled.to_string();
+        let assertions = self.assertions().map(|a| a.to_string());
+        let timestamp = self
+            .timestamp
+            .map(|timestamp| format_timestamp(timestamp, options.dialect))
+            .transpose()?;
+        let time = format_time(self.time(), options, warnings);
+        let filepath = self.filepath.as_deref().map(|f| normalize_path(f, options));
+        let attributes = match options.dialect {
+            Dialect::Default => {
+                let mut attributes = vec![
+                    ("id", id_attr.as_str()),
+                    ("name", &self.name),
+                    ("package", &self.package),
+                    ("tests", &tests),
+                    ("errors", &errors),
+                    ("failures", &failures),
+                    ("skipped", &skipped),
+                ];
+                if disabled > 0 {
+                    attributes.push(("disabled", &disabled_attr));
+                }
+                if let Some(assertions) = &assertions {
+                    attributes.push(("assertions", assertions));
+                }
+                attributes.push(("hostname", &self.hostname));
+                if let Some(filepath) = &filepath {
+                    attributes.push(("file", filepath));
+                }
+                if let Some(timestamp) = &timestamp {
+                    attributes.push(("timestamp", timestamp));
+                }
+                attributes.push(("time", &time));
+                attributes
+            }
+            Dialect::Ant => {
+                let mut attributes = vec![
+                    ("id", id_attr.as_str()),
+                    ("name", &self.name),
+                    ("package", &self.package),
+                    ("tests", &tests),
+                    ("errors", &errors),
+                    ("failures", &failures),
+                    ("hostname", &self.hostname),
+                ];
+                if let Some(timestamp) = &timestamp {
+                    attributes.push(("timestamp", timestamp));
+                }
+                attributes.push(("time", &time));
+                attributes
+            }
+            Dialect::Pytest => {
+                let mut attributes = vec![
+                    ("name", self.name.as_str()),
+                    ("errors", &errors),
+                    ("failures", &failures),
+                    ("skipped", &skipped),
+                    ("tests", &tests),
+                    ("time", &time),
+                ];
+                if let Some(timestamp) = &timestamp {
+                    attributes.push(("timestamp", timestamp));
+                }
+                attributes.push(("hostname", &self.hostname));
+                attributes
+            }
+        };
+        let (system_out, system_err) = match options.dialect {
+            Dialect::Ant => (
+                vec![Cow::Owned(self.system_out.concat())],
+                vec![Cow::Owned(self.system_err.concat())],
+            ),
+            _ => (
+                output(&self.system_out, options),
+                output(&self.system_err, options),
+            ),
+        };
+        let empty_properties = options.dialect == Dialect::Ant && self.properties.is_empty();
+        w.create_element("testsuite")
+            .with_attributes(attributes)
             .write_empty_or_inner(
-                |_| self.testsuites.is_empty(),
+                options,
+                |_| {
+                    self.properties.is_empty()
+                        && !empty_properties
+                        && self.testcases.is_empty()
+                        && system_out.is_empty()
+                        && system_err.is_empty()
+                },
                 |w| {
-                    w.write_iter(self.testsuites.iter().enumerate(), |w, (id, ts)| {
-                        w.create_element("testsuite")
-                            .with_attributes([
-                                ("id", id.to_string().as_str()),
-                                ("name", &ts.name),
-                                ("package", &ts.package),
-                                ("tests", &ts.tests().to_string()),
-                                ("errors", &ts.errors().to_string()),
-                                ("failures", &ts.failures().to_string()),
-                                ("hostname", &ts.hostname),
-                                ("timestamp", &ts.timestamp.format(&Rfc3339).unwrap()),
-                                ("time", &ts.time().as_seconds_f64().to_string()),
-                            ])
-                            .write_empty_or_inner(
-                                |_| {
-                                    ts.testcases.is_empty()
-                                        && ts.system_out.is_none()
-                                        && ts.system_err.is_none()
-                                },
-                                |w| {
-                                    w.write_iter(ts.testcases.iter(), |w, tc| tc.write_xml(w))?
-                                        .write_opt(ts.system_out.as_ref(), |writer, out| {
-                                            writer
-                                                .create_element("system-out")
-                                                .write_cdata_content(BytesCData::new(out))
-                                        })?
-                                        .write_opt(ts.system_err.as_ref(), |writer, err| {
-                                            writer
-                                                .create_element("system-err")
-                                                .write_cdata_content(BytesCData::new(err))
-                                        })
-                                        .map(drop)
-                                },
-                            )
+                    let w = if empty_properties {
+                        w.create_element("properties").write_empty_with(options)?
+                    } else {
+                        w.write_properties(&self.properties, options)?
+                    };
+                    w.write_iter(self.testcases.iter().enumerate(), |w, (i, tc)| {
+                        warnings
+                            .scope(Some(id), Some(i), || tc.write_xml(w, options, warnings))
+                            .map_err(|e| e.at(id, Some(i)))
+                    })?
+                    .write_iter(&system_out, |writer, out| {
+                        writer
+                            .create_element("system-out")
+                            .write_text_content_with(out.as_ref(), options)
+                            .map_err(Error::from)
+                    })?
+                    .write_iter(&system_err, |writer, err| {
+                        writer
+                            .create_element("system-err")
+                            .write_text_content_with(err.as_ref(), options)
+                            .map_err(Error::from)
                     })
                     .map(drop)
                 },
             )
-            .map(drop)
+    }
+
+    /// Estimate the size of the `<testsuite>` element, see [`Report::estimated_xml_size`].
+    fn estimated_xml_size(&self) -> usize {
+        170 + 2 * self.name.len()
+            + self.package.len()
+            + self.hostname.len()
+            + self.filepath.as_ref().map_or(0, |f| 8 + f.len())
+            + estimated_properties_size(&self.properties)
+            + self
+                .testcases
+                .iter()
+                .map(TestCase::estimated_xml_size)
+                .sum::<usize>()
+            + estimated_output_size(&self.system_out)
+            + estimated_output_size(&self.system_err)
     }
 }
 
 impl TestCase {
     /// Write the XML version of the [`TestCase`] to the given [`Writer`].
-    fn write_xml<'a, W: Write>(&self, w: &'a mut Writer<W>) -> Result<&'a mut Writer<W>> {
-        let time = self.time.as_seconds_f64().to_string();
+    fn write_xml<'a, W: Write>(
+        &self,
+        w: &'a mut Writer<W>,
+        options: &ReportOptions,
+        warnings: &Warnings,
+    ) -> Result<&'a mut Writer<W>, Error> {
+        let time = match options.testcase_time {
+            TestCaseTime::Always => true,
+            TestCaseTime::OmitZeroSkipped => {
+                !(self.time.is_zero() && (self.is_skipped() || self.is_disabled()))
+            }
+            TestCaseTime::Omit => false,
+        }
+        .then(|| format_time(self.time, options, warnings));
+        let status = match self.status() {
+            Status::Skipped | Status::Disabled => "notrun",
+            Status::Passed | Status::Failed | Status::Errored => "run",
+        };
+        let filepath = self.filepath.as_deref().map(|f| normalize_path(f, options));
+        // Only the default dialect writes the timestamp of test cases
+        let timestamp = match options.dialect {
+            Dialect::Default => self
+                .timestamp
+                .map(|timestamp| format_timestamp(timestamp, options.dialect))
+                .transpose()?,
+            Dialect::Pytest | Dialect::Ant => None,
+        };
+        let assertions = self.assertions.map(|a| a.to_string());
+        let line = self.line.map(|l| l.to_string());
+        let attributes = match options.dialect {
+            Dialect::Default => [
+                Some(("name", self.name.as_str())),
+                time.as_deref().map(|t| ("time", t)),
+                assertions.as_deref().map(|a| ("assertions", a)),
+                self.classname.as_ref().map(|cl| ("classname", cl.as_str())),
+                filepath.as_deref().map(|f| ("file", f)),
+                line.as_deref().map(|l| ("line", l)),
+                self.hostname.as_deref().map(|h| ("hostname", h)),
+                self.group.as_deref().map(|g| ("group", g)),
+                timestamp.as_deref().map(|t| ("timestamp", t)),
+            ],
+            Dialect::Pytest => [
+                Some(("classname", self.classname.as_deref().unwrap_or_default())),
+                Some(("name", self.name.as_str())),
+                time.as_deref().map(|t| ("time", t)),
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+            ],
+            Dialect::Ant => [
+                Some(("name", self.name.as_str())),
+                Some(("classname", self.classname.as_deref().unwrap_or_default())),
+                time.as_deref().map(|t| ("time", t)),
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+            ],
+        };
+        let (properties, system_out, system_err) = match options.dialect {
+            Dialect::Ant => (&[][..], Vec::new(), Vec::new()),
+            _ => (
+                self.properties.as_slice(),
+                output(&self.system_out, options),
+                output(&self.system_err, options),
+            ),
+        };
         w.create_element("testcase")
+            .with_attributes(
+                attributes
+                    .into_iter()
+                    .chain([options.testcase_status.then_some(("status", status))])
+                    .flatten(),
+            )
+            .write_empty_or_inner(
+                options,
+                |_| {
+                    self.is_success()
+                        && self.additional_results.is_empty()
+                        && properties.is_empty()
+                        && system_out.is_empty()
+                        && system_err.is_empty()
+                },
+                |w| {
+                    self.result
+                        .write_xml(w.write_properties(properties, options)?, options)?
+                        .write_iter(&self.additional_results, |w, r| r.write_xml(w, options))?
+                        .write_iter(&system_out, |w, out| {
+                            w.create_element("system-out")
+                                .write_text_content_with(out.as_ref(), options)
+                        })?
+                        .write_iter(&system_err, |w, err| {
+                            w.create_element("system-err")
+                                .write_text_content_with(err.as_ref(), options)
+                        })
+                        .map(drop)
+                },
+            )
+            .map_err(Error::from)
+    }
+
+    /// Estimate the size of the `<testcase>` element, see [`Report::estimated_xml_size`].
+    fn estimated_xml_size(&self) -> usize {
+        45 + self.name.len()
+            + self.classname.as_ref().map_or(0, |c| 12 + c.len())
+            + self.filepath.as_ref().map_or(0, |f| 8 + f.len())
+            + self.line.map_or(0, |_| 10)
+            + self.hostname.as_ref().map_or(0, |h| 12 + h.len())
+            + self.group.as_ref().map_or(0, |g| 9 + g.len())
+            + self.timestamp.map_or(0, |_| 37)
+            + self.assertions.map_or(0, |_| 16)
+            + estimated_properties_size(&self.properties)
+            + self.result.estimated_xml_size()
+            + self
+                .additional_results
+                .iter()
+                .map(TestResult::estimated_xml_size)
+                .sum::<usize>()
+            + estimated_output_size(&self.system_out)
+            + estimated_output_size(&self.system_err)
+    }
+}
+
+impl TestResult {
+    /// Write the XML version of the [`TestResult`] to the given [`Writer`].
+    fn write_xml<'a, W: Write>(
+        &self,
+        w: &'a mut Writer<W>,
+        options: &ReportOptions,
+    ) -> Result<&'a mut Writer<W>, quick_xml::Error> {
+        let (element, type_, message, cause, file, line) = match self {
+            TestResult::Success => return Ok(w),
+            TestResult::Skipped { .. } | TestResult::Disabled => {
+                let message = match self {
+                    TestResult::Skipped { message } => message.as_deref(),
+                    _ => None,
+                };
+                let skipped = w.create_element("skipped");
+                return match options.dialect {
+                    Dialect::Default if self == &TestResult::Disabled => skipped
+                        .with_attribute(("type", "disabled"))
+                        .write_empty_with(options),
+                    Dialect::Default => skipped
+                        .with_attributes(message.map(|m| ("message", m)))
+                        .write_empty_with(options),
+                    Dialect::Ant => skipped.write_empty_with(options),
+                    Dialect::Pytest => skipped
+                        .with_attributes([
+                            ("type", "pytest.skip"),
+                            ("message", message.unwrap_or_default()),
+                        ])
+                        .write_empty_with(options),
+                };
+            }
+            TestResult::Error {
+                type_,
+                message,
+                cause,
+                file,
+                line,
+            } => ("error", type_, message, cause, file, line),
+            TestResult::Failure {
+                type_,
+                message,
+                cause,
+                file,
+                line,
+            } => ("failure", type_, message, cause, file, line),
+        };
+        let (type_, file, line) = match options.dialect {
+            Dialect::Default => (Some(type_), file.as_deref(), *line),
+            Dialect::Pytest => (None, None, None),
+            Dialect::Ant => (Some(type_), None, None),
+        };
+        let file = file.map(|f| normalize_path(f, options));
+        let line = line.map(|l| l.to_string());
+        let (message, cause) = match options.long_message_to_body {
+            Some(max) if message.chars().count() > max || message.contains('\n') => {
+                let first_line = message.lines().next().unwrap_or_default();
+                let mut short: String = first_line.chars().take(max).collect();
+                if short.len() < message.len() {
+                    short.push_str("...");
+                }
+                let body = match cause {
+                    Some(cause) => format!("{}\n{}", message, cause),
+                    None => message.clone(),
+                };
+                (Cow::Owned(short), Some(Cow::Owned(body)))
+            }
+            _ => (
+                Cow::Borrowed(message.as_str()),
+                cause.as_deref().map(Cow::Borrowed),
+            ),
+        };
+        w.create_element(element)
             .with_attributes(
                 [
-                    Some(("name", self.name.as_str())),
-                    Some(("time", time.as_str())),
-                    self.classname.as_ref().map(|cl| ("classname", cl.as_str())),
-                    self.filepath.as_ref().map(|f| ("file", f.as_str())),
+                    type_.map(|t| ("type", t.as_str())),
+                    Some(("message", message.as_ref())),
+                    file.as_deref().map(|f| ("file", f)),
+                    line.as_deref().map(|l| ("line", l)),
                 ]
                 .into_iter()
                 .flatten(),
             )
             .write_empty_or_inner(
-                |_| {
-                    matches!(self.result, TestResult::Success)
-                        && self.system_out.is_none()
-                        && self.system_err.is_none()
-                },
+                options,
+                |_| cause.is_none(),
                 |w| {
-                    match self.result {
-                        TestResult::Success => Ok(w),
-                        TestResult::Error {
-                            ref type_,
-                            ref message,
-                            ref cause,
-                        } => w
-                            .create_element("error")
-                            .with_attributes([
-                                ("type", type_.as_str()),
-                                ("message", message.as_str()),
-                            ])
-                            .write_empty_or_inner(
-                                |_| cause.is_none(),
-                                |w| {
-                                    w.write_opt(cause.as_ref(), |w, cause| {
-                                        let data = BytesCData::new(cause.as_str());
-                                        w.write_event(Event::CData(BytesCData::new(
-                                            String::from_utf8_lossy(&data),
-                                        )))
-                                        .map(|_| w)
-                                    })
-                                    .map(drop)
-                                },
-                            ),
-                        TestResult::Failure {
-                            ref type_,
-                            ref message,
-                            ref cause,
-                        } => w
-                            .create_element("failure")
-                            .with_attributes([
-                                ("type", type_.as_str()),
-                                ("message", message.as_str()),
-                            ])
-                            .write_empty_or_inner(
-                                |_| cause.is_none(),
-                                |w| {
-                                    w.write_opt(cause.as_ref(), |w, cause| {
-                                        let data = BytesCData::new(cause.as_str());
-                                        w.write_event(Event::CData(BytesCData::new(
-                                            String::from_utf8_lossy(&data),
-                                        )))
-                                        .map(|_| w)
-                                    })
-                                    .map(drop)
-                                },
-                            ),
-                        TestResult::Skipped => w.create_element("skipped").write_empty(),
-                    }?
-                    .write_opt(self.system_out.as_ref(), |w, out| {
-                        w.create_element("system-out")
-                            .write_cdata_content(BytesCData::new(out.as_str()))
-                    })?
-                    .write_opt(self.system_err.as_ref(), |w, err| {
-                        w.create_element("system-err")
-                            .write_cdata_content(BytesCData::new(err.as_str()))
-                    })
-                    .map(drop)
+                    w.write_opt(cause.as_ref(), |w, cause| w.write_text(cause, options))
+                        .map(drop)
                 },
             )
     }
+
+    /// Estimate the size of the result element, see [`Report::estimated_xml_size`].
+    fn estimated_xml_size(&self) -> usize {
+        match self {
+            TestResult::Success => 0,
+            TestResult::Skipped { message } => 25 + message.as_ref().map_or(0, |m| 11 + m.len()),
+            TestResult::Disabled => 25,
+            TestResult::Error {
+                type_,
+                message,
+                cause,
+                file,
+                ..
+            }
+            | TestResult::Failure {
+                type_,
+                message,
+                cause,
+                file,
+                ..
+            } => {
+                50 + type_.len()
+                    + message.len()
+                    + cause.as_ref().map_or(0, String::len)
+                    + file.as_ref().map_or(0, |f| 20 + f.len())
+            }
+        }
+    }
+}
+
+/// Estimated size of a `<properties>` element.
+fn estimated_properties_size(properties: &[Property]) -> usize {
+    if properties.is_empty() {
+        return 0;
+    }
+    25 + properties
+        .iter()
+        .map(|p| 28 + p.name.len() + p.value.len())
+        .sum::<usize>()
+}
+
+/// Estimated size of `<system-out>` or `<system-err>` elements with the given `output`.
+fn estimated_output_size<S: AsRef<str>>(output: impl IntoIterator<Item = S>) -> usize {
+    output.into_iter().map(|o| 37 + o.as_ref().len()).sum()
 }
 
 /// Builder for JUnit [`Report`](struct.Report.html) objects
-#[derive(Default, Debug, Clone, Getters)]
+#[derive(Default, Debug, Clone)]
 pub struct ReportBuilder {
     report: Report,
+    sort_by_timestamp: bool,
 }
 
 impl ReportBuilder {
+    /// The `Report` being built
+    pub fn report(&self) -> &Report {
+        &self.report
+    }
+
     /// Create a new empty ReportBuilder
     pub fn new() -> ReportBuilder {
         ReportBuilder {
             report: Report::new(),
+            sort_by_timestamp: false,
         }
     }
 
@@ -210,37 +1181,389 @@ impl ReportBuilder {
         self
     }
 
+    /// Set the name of the report, see [`Report::set_name`].
+    pub fn set_name(&mut self, name: &str) -> &mut Self {
+        self.report.set_name(name);
+        self
+    }
+
+    /// Add a report-level [`Property`](struct.Property.html) to this report builder.
+    pub fn add_property(&mut self, name: &str, value: &str) -> &mut Self {
+        self.report.add_property(name, value);
+        self
+    }
+
+    /// Set the report-level `system_out`.
+    pub fn set_system_out(&mut self, system_out: &str) -> &mut Self {
+        self.report.set_system_out(system_out);
+        self
+    }
+
+    /// Set the report-level `system_err`.
+    pub fn set_system_err(&mut self, system_err: &str) -> &mut Self {
+        self.report.set_system_err(system_err);
+        self
+    }
+
+    /// Order the test suites by their timestamp when building the report.
+    ///
+    /// Suites without timestamp come first, suites with equal timestamps keep the
+    /// order in which they were added.
+    /// The `id` of each suite follows the sorted order.
+    pub fn sort_by_timestamp(&mut self) -> &mut Self {
+        self.sort_by_timestamp = true;
+        self
+    }
+
     /// Build and return a [`Report`](struct.Report.html) object based on the data stored in this ReportBuilder object.
+    #[must_use]
     pub fn build(&self) -> Report {
-        self.report.clone()
+        let mut report = self.report.clone();
+        if self.sort_by_timestamp {
+            report.testsuites.sort_by_key(|ts| ts.timestamp);
+        }
+        report
+    }
+}
+
+/// Add test suites from an iterator, same as [`Report::add_testsuites`].
+impl Extend<TestSuite> for Report {
+    fn extend<T: IntoIterator<Item = TestSuite>>(&mut self, iter: T) {
+        self.add_testsuites(iter);
+    }
+}
+
+/// Add a test suite, same as [`Report::add_testsuite`].
+///
+/// ```rust
+/// use junit_report::{Report, TestSuite};
+///
+/// let mut r = Report::new();
+/// for name in ["ts1", "ts2"] {
+///     r += TestSuite::new(name);
+/// }
+/// assert_eq!(r.testsuites().len(), 2);
+/// ```
+impl AddAssign<TestSuite> for Report {
+    fn add_assign(&mut self, testsuite: TestSuite) {
+        self.add_testsuite(testsuite);
+    }
+}
+
+/// Add test suites from an iterator, same as [`ReportBuilder::add_testsuites`].
+///
+/// ```rust
+/// use junit_report::{ReportBuilder, TestSuite};
+///
+/// let mut builder = ReportBuilder::new();
+/// builder.extend(["ts1", "ts2"].into_iter().map(TestSuite::new));
+/// assert_eq!(builder.build().testsuites().len(), 2);
+/// ```
+impl Extend<TestSuite> for ReportBuilder {
+    fn extend<T: IntoIterator<Item = TestSuite>>(&mut self, iter: T) {
+        self.add_testsuites(iter);
+    }
+}
+
+/// Add a test suite, same as [`ReportBuilder::add_testsuite`].
+///
+/// ```rust
+/// use junit_report::{ReportBuilder, TestSuite};
+///
+/// let mut builder = ReportBuilder::new();
+/// for name in ["ts1", "ts2"] {
+///     builder += TestSuite::new(name);
+/// }
+/// assert_eq!(builder.build().testsuites().len(), 2);
+/// ```
+impl AddAssign<TestSuite> for ReportBuilder {
+    fn add_assign(&mut self, testsuite: TestSuite) {
+        self.add_testsuite(testsuite);
+    }
+}
+
+/// Format the `timestamp` attribute of a test suite or test case, the Ant dialect
+/// omits fractional seconds and the UTC offset.
+///
+/// Timestamps outside of the years 0000 to 9999 cannot be written as RFC 3339 and
+/// fail with [`Error::Validation`] in the other dialects.
+pub(crate) fn format_timestamp(
+    timestamp: OffsetDateTime,
+    dialect: Dialect,
+) -> Result<String, Error> {
+    match dialect {
+        Dialect::Ant => timestamp.format(format_description!(
+            "[year]-[month]-[day]T[hour]:[minute]:[second]"
+        )),
+        Dialect::Default | Dialect::Pytest => timestamp.format(&Rfc3339),
+    }
+    .map_err(|e| Error::Validation(format!("cannot write timestamp {}: {}", timestamp, e)))
+}
+
+/// Format a [`Duration`] as the number of seconds used in `time` attributes.
+///
+/// Negative times are written as zero and times above [`ReportOptions::max_time`]
+/// as the maximum, both are recorded in `warnings`. A negative maximum is treated as zero.
+fn format_time(time: Duration, options: &ReportOptions, warnings: &Warnings) -> String {
+    let max = options
+        .max_time
+        .map_or(Duration::MAX, |max| max.max(Duration::ZERO));
+    let clamped = time.max(Duration::ZERO).min(max);
+    if clamped != time {
+        warnings.clamped.set(true);
+    }
+    let time = clamped;
+    let time = match options.time_unit {
+        TimeUnit::Seconds => time.as_seconds_f64(),
+        TimeUnit::Milliseconds => time.whole_nanoseconds() as f64 / 1_000_000.0,
+    };
+    format_seconds(time, options.time_precision)
+}
+
+/// Format a `time` value as `xs:decimal`, with `places` decimal places if given.
+///
+/// Rust's float formatting never depends on the locale, the decimal separator is
+/// always `.` and no exponent or thousands separator is written. The shortest
+/// representation that reads back as the same value is used without `places`.
+pub(crate) fn format_seconds(secs: f64, places: Option<usize>) -> String {
+    match places {
+        Some(places) => format!("{:.*}", places, secs),
+        None => format!("{}", secs),
+    }
+}
+
+/// The output blocks to write, skipping empty ones if `omit_empty_output` is set.
+fn output<'a>(
+    output: impl IntoIterator<Item = &'a String>,
+    options: &ReportOptions,
+) -> Vec<Cow<'a, str>> {
+    output
+        .into_iter()
+        .filter(|o| !(options.omit_empty_output && o.is_empty()))
+        .map(|o| Cow::Borrowed(o.as_str()))
+        .collect()
+}
+
+/// Normalize a `file` attribute according to the given [`ReportOptions`].
+fn normalize_path<'a>(path: &'a str, options: &ReportOptions) -> Cow<'a, str> {
+    let mut path = Cow::Borrowed(path);
+    if options.path_forward_slashes && path.contains('\\') {
+        path = Cow::Owned(path.replace('\\', "/"));
+    }
+    if let Some(prefix) = &options.path_prefix_strip {
+        let prefix = prefix.to_string_lossy();
+        let prefix = if options.path_forward_slashes {
+            Cow::Owned(prefix.replace('\\', "/"))
+        } else {
+            prefix
+        };
+        if let Ok(relative) = Path::new(path.as_ref()).strip_prefix(prefix.as_ref()) {
+            path = Cow::Owned(relative.to_string_lossy().into_owned());
+        }
+    }
+    path
+}
+
+/// Length of the UTF-8 encoded character at the start of `bytes` if it is not allowed
+/// in XML 1.0, `0` otherwise.
+///
+/// These are the ASCII control characters but tab and newlines and the noncharacters
+/// U+FFFE and U+FFFF. Their encodings never occur inside other UTF-8 sequences, so
+/// they can be removed from the encoded output without breaking other characters.
+fn invalid_xml_char_len(bytes: &[u8]) -> usize {
+    match bytes {
+        [b, ..] if *b < 0x20 && !matches!(b, b'\t' | b'\n' | b'\r') => 1,
+        [0xEF, 0xBF, 0xBE | 0xBF, ..] => 3,
+        _ => 0,
+    }
+}
+
+/// Sink removing the characters that are not allowed in XML 1.0.
+///
+/// The XML writer always passes complete characters, so they are never split between
+/// two calls of `write`.
+struct InvalidCharsWriter<'a, W> {
+    inner: W,
+    /// Number of characters removed so far
+    stripped: &'a Cell<usize>,
+}
+
+impl<'a, W> InvalidCharsWriter<'a, W> {
+    fn new(inner: W, stripped: &'a Cell<usize>) -> Self {
+        InvalidCharsWriter { inner, stripped }
+    }
+}
+
+impl<W: Write> Write for InvalidCharsWriter<'_, W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let mut start = 0;
+        let mut i = 0;
+        while i < buf.len() {
+            match invalid_xml_char_len(&buf[i..]) {
+                0 => i += 1,
+                len => {
+                    self.inner.write_all(&buf[start..i])?;
+                    self.stripped.set(self.stripped.get() + 1);
+                    i += len;
+                    start = i;
+                }
+            }
+        }
+        self.inner.write_all(&buf[start..])?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// The [`WriteWarning`]s collected while writing a report.
+///
+/// Adjustments are attributed to the innermost [`scope`](#method.scope) they happen in.
+struct Warnings<'a> {
+    /// Number of characters removed by the [`InvalidCharsWriter`] so far
+    stripped: &'a Cell<usize>,
+    /// Number of removed characters attributed to a finished scope
+    claimed: Cell<usize>,
+    /// Whether a time of the current scope was clamped
+    clamped: Cell<bool>,
+    warnings: RefCell<Vec<WriteWarning>>,
+}
+
+impl<'a> Warnings<'a> {
+    fn new(stripped: &'a Cell<usize>) -> Self {
+        Warnings {
+            stripped,
+            claimed: Cell::new(stripped.get()),
+            clamped: Cell::new(false),
+            warnings: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// Run `write` and attribute the adjustments it makes outside of nested scopes to
+    /// the given position.
+    fn scope<T>(
+        &self,
+        suite_index: Option<usize>,
+        testcase_index: Option<usize>,
+        write: impl FnOnce() -> T,
+    ) -> T {
+        let stripped = self.stripped.get();
+        let claimed = self.claimed.get();
+        let clamped = self.clamped.replace(false);
+        let result = write();
+
+        let own = (self.stripped.get() - stripped) - (self.claimed.get() - claimed);
+        self.claimed.set(self.claimed.get() + own);
+        let mut warnings = self.warnings.borrow_mut();
+        if self.clamped.replace(clamped) {
+            warnings.push(WriteWarning::ClampedTime {
+                suite_index,
+                testcase_index,
+            });
+        }
+        if own > 0 {
+            warnings.push(WriteWarning::StrippedCharacters {
+                suite_index,
+                testcase_index,
+            });
+        }
+        result
+    }
+
+    /// The collected warnings, ordered by their position in the report.
+    fn into_vec(self) -> Vec<WriteWarning> {
+        let mut warnings = self.warnings.into_inner();
+        warnings.sort_by_key(WriteWarning::position);
+        warnings
+    }
+}
+
+/// Sink converting the newlines written to it according to a [`LineEnding`].
+struct LineEndingWriter<W> {
+    inner: W,
+    line_ending: LineEnding,
+    /// Whether the last byte written was a `\r`
+    last_cr: bool,
+}
+
+impl<W: Write> LineEndingWriter<W> {
+    fn new(inner: W, line_ending: LineEnding) -> Self {
+        LineEndingWriter {
+            inner,
+            line_ending,
+            last_cr: false,
+        }
+    }
+}
+
+impl<W: Write> Write for LineEndingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        if self.line_ending == LineEnding::Lf {
+            return self.inner.write(buf);
+        }
+        let mut start = 0;
+        for (i, &b) in buf.iter().enumerate() {
+            let after_cr = if i == 0 {
+                self.last_cr
+            } else {
+                buf[i - 1] == b'\r'
+            };
+            if b == b'\n' && !after_cr {
+                self.inner.write_all(&buf[start..i])?;
+                self.inner.write_all(b"\r\n")?;
+                start = i + 1;
+            }
+        }
+        self.inner.write_all(&buf[start..])?;
+        if let Some(&last) = buf.last() {
+            self.last_cr = last == b'\r';
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
     }
 }
 
 /// [`Writer`] extension.
 trait WriterExt {
     /// [`Write`]s in case `val` is [`Some`] or does nothing otherwise.
-    fn write_opt<T>(
+    fn write_opt<T, E>(
         &mut self,
         val: Option<T>,
-        inner: impl FnOnce(&mut Self, T) -> Result<&mut Self>,
-    ) -> Result<&mut Self>;
+        inner: impl FnOnce(&mut Self, T) -> Result<&mut Self, E>,
+    ) -> Result<&mut Self, E>;
 
     /// [`Write`]s every item of the [`Iterator`].
-    fn write_iter<T, I>(
+    fn write_iter<T, I, E>(
         &mut self,
         val: I,
-        inner: impl FnMut(&mut Self, T) -> Result<&mut Self>,
-    ) -> Result<&mut Self>
+        inner: impl FnMut(&mut Self, T) -> Result<&mut Self, E>,
+    ) -> Result<&mut Self, E>
     where
         I: IntoIterator<Item = T>;
+
+    /// [`Write`]s a `<properties>` element in case `properties` is not empty.
+    fn write_properties(
+        &mut self,
+        properties: &[Property],
+        options: &ReportOptions,
+    ) -> quick_xml::Result<&mut Self>;
+
+    /// [`Write`]s `text` as CDATA, split into several sections if it contains `]]>`,
+    /// or as escaped text depending on the [`TextMode`] of `options`.
+    fn write_text(&mut self, text: &str, options: &ReportOptions) -> quick_xml::Result<&mut Self>;
 }
 
 impl<W: Write> WriterExt for Writer<W> {
-    fn write_opt<T>(
+    fn write_opt<T, E>(
         &mut self,
         val: Option<T>,
-        inner: impl FnOnce(&mut Self, T) -> Result<&mut Self>,
-    ) -> Result<&mut Self> {
+        inner: impl FnOnce(&mut Self, T) -> Result<&mut Self, E>,
+    ) -> Result<&mut Self, E> {
         if let Some(val) = val {
             inner(self, val)
         } else {
@@ -248,44 +1571,107 @@ impl<W: Write> WriterExt for Writer<W> {
         }
     }
 
-    fn write_iter<T, I>(
+    fn write_iter<T, I, E>(
         &mut self,
         iter: I,
-        inner: impl FnMut(&mut Self, T) -> Result<&mut Self>,
-    ) -> Result<&mut Self>
+        inner: impl FnMut(&mut Self, T) -> Result<&mut Self, E>,
+    ) -> Result<&mut Self, E>
     where
         I: IntoIterator<Item = T>,
     {
         iter.into_iter().try_fold(self, inner)
     }
+
+    fn write_properties(
+        &mut self,
+        properties: &[Property],
+        options: &ReportOptions,
+    ) -> quick_xml::Result<&mut Self> {
+        if properties.is_empty() {
+            return Ok(self);
+        }
+        self.create_element("properties").write_inner_content(|w| {
+            w.write_iter(properties, |w, p| {
+                w.create_element("property")
+                    .with_attributes([("name", p.name.as_str()), ("value", p.value.as_str())])
+                    .write_empty_with(options)
+            })
+            .map(drop)
+        })
+    }
+
+    fn write_text(&mut self, text: &str, options: &ReportOptions) -> quick_xml::Result<&mut Self> {
+        if options.text_mode == TextMode::Escaped {
+            self.write_event(Event::Text(BytesText::new(text)))?;
+            return Ok(self);
+        }
+        let mut rest = text;
+        while let Some(end) = rest.find("]]>") {
+            self.write_event(Event::CData(BytesCData::new(&rest[..end + 2])))?;
+            rest = &rest[end + 2..];
+        }
+        self.write_event(Event::CData(BytesCData::new(rest)))?;
+        Ok(self)
+    }
 }
 
 /// [`ElementWriter`] extension.
 trait ElementWriterExt<'a, W: Write> {
     /// [`Writes`] with `inner` in case `is_empty` resolves to [`false`] or
     /// [`Write`]s with [`ElementWriter::write_empty`] otherwise.
-    fn write_empty_or_inner<Inner>(
+    fn write_empty_or_inner<Inner, E>(
         self,
+        options: &ReportOptions,
         is_empty: impl FnOnce(&mut Self) -> bool,
         inner: Inner,
-    ) -> Result<&'a mut Writer<W>>
+    ) -> Result<&'a mut Writer<W>, E>
     where
-        Inner: Fn(&mut Writer<W>) -> Result<()>;
+        Inner: Fn(&mut Writer<W>) -> Result<(), E>,
+        E: From<quick_xml::Error>;
+
+    /// [`Write`]s the element with `text` as content, see [`WriterExt::write_text`].
+    fn write_text_content_with(
+        self,
+        text: &str,
+        options: &ReportOptions,
+    ) -> quick_xml::Result<&'a mut Writer<W>>;
+
+    /// [`Write`]s the element without content, as `<element/>` or as
+    /// `<element></element>` if [`ReportOptions::expand_empty_elements`] is set.
+    fn write_empty_with(self, options: &ReportOptions) -> quick_xml::Result<&'a mut Writer<W>>;
 }
 
 impl<'a, W: Write> ElementWriterExt<'a, W> for ElementWriter<'a, W> {
-    fn write_empty_or_inner<Inner>(
+    fn write_empty_or_inner<Inner, E>(
         mut self,
+        options: &ReportOptions,
         is_empty: impl FnOnce(&mut Self) -> bool,
         inner: Inner,
-    ) -> Result<&'a mut Writer<W>>
+    ) -> Result<&'a mut Writer<W>, E>
     where
-        Inner: Fn(&mut Writer<W>) -> Result<()>,
+        Inner: Fn(&mut Writer<W>) -> Result<(), E>,
+        E: From<quick_xml::Error>,
     {
         if is_empty(&mut self) {
-            self.write_empty()
+            Ok(self.write_empty_with(options)?)
         } else {
             self.write_inner_content(inner)
         }
     }
+
+    fn write_text_content_with(
+        self,
+        text: &str,
+        options: &ReportOptions,
+    ) -> quick_xml::Result<&'a mut Writer<W>> {
+        self.write_inner_content(|w| w.write_text(text, options).map(drop))
+    }
+
+    fn write_empty_with(self, options: &ReportOptions) -> quick_xml::Result<&'a mut Writer<W>> {
+        if options.expand_empty_elements {
+            self.write_inner_content(|_| Ok(()))
+        } else {
+            self.write_empty()
+        }
+    }
 }
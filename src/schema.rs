@@ -0,0 +1,540 @@
+/*
+ * Copyright (c) 2018 Pascal Bach
+ * Copyright (c) 2021 Siemens Mobility GmbH
+ *
+ * SPDX-License-Identifier:     MIT
+ */
+
+use std::collections::HashMap;
+use std::fmt;
+
+use quick_xml::{
+    events::{BytesStart, Event},
+    Reader,
+};
+use time::{format_description::well_known::Rfc3339, OffsetDateTime};
+
+use crate::Report;
+
+/// The JUnit XML Schema describing the reports written by this crate.
+///
+/// [`Report::validate_against_xsd`] checks reports against the rules of this schema.
+pub const JUNIT_XSD: &str = include_str!("junit.xsd");
+
+/// A violation of the [`JUNIT_XSD`] schema found by [`Report::validate_against_xsd`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationError {
+    /// Location of the offending element, e.g. `/testsuites/testsuite[0]/testcase[1]`
+    path: String,
+    message: String,
+}
+
+impl ValidationError {
+    /// Location of the offending element, e.g. `/testsuites/testsuite[0]/testcase[1]`
+    pub fn path(&self) -> &String {
+        &self.path
+    }
+
+    /// Description of the violation
+    pub fn message(&self) -> &String {
+        &self.message
+    }
+
+    fn new(path: &str, message: String) -> Self {
+        ValidationError {
+            path: path.to_owned(),
+            message,
+        }
+    }
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path, self.message)
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+impl Report {
+    /// Validate the XML written by [`write_xml`](#method.write_xml) against [`JUNIT_XSD`].
+    ///
+    /// The check is done in Rust without any external tools. It covers the subset of XSD
+    /// constraints relevant to the reports written by this crate: known elements and their
+    /// nesting, order and number of occurrences, required attributes and the types of
+    /// numeric, decimal and date time attributes.
+    ///
+    /// All violations found are returned.
+    pub fn validate_against_xsd(&self) -> Result<(), Vec<ValidationError>> {
+        let mut out = Vec::new();
+        self.write_xml(&mut out)
+            .map_err(|e| vec![ValidationError::new("/", e.to_string())])?;
+        validate_xml(&out)
+    }
+}
+
+/// Type of an attribute value.
+#[derive(Clone, Copy)]
+enum Kind {
+    String,
+    NonNegativeInteger,
+    Decimal,
+    DateTime,
+}
+
+/// Declaration of an attribute.
+struct Attribute {
+    name: &'static str,
+    kind: Kind,
+    required: bool,
+}
+
+/// Declaration of a child element in a sequence.
+struct Child {
+    name: &'static str,
+    min: usize,
+    max: Option<usize>,
+}
+
+/// Declaration of an element.
+struct Element {
+    name: &'static str,
+    attributes: &'static [Attribute],
+    children: &'static [Child],
+    text: bool,
+}
+
+const fn attr(name: &'static str, kind: Kind, required: bool) -> Attribute {
+    Attribute {
+        name,
+        kind,
+        required,
+    }
+}
+
+const fn child(name: &'static str, min: usize, max: Option<usize>) -> Child {
+    Child { name, min, max }
+}
+
+const RESULT_ATTRIBUTES: &[Attribute] = &[
+    attr("type", Kind::String, false),
+    attr("message", Kind::String, false),
+    attr("file", Kind::String, false),
+    attr("line", Kind::NonNegativeInteger, false),
+];
+
+/// The elements of [`JUNIT_XSD`].
+const ELEMENTS: &[Element] = &[
+    Element {
+        name: "testsuites",
+        attributes: &[
+            attr("name", Kind::String, false),
+            attr("time", Kind::Decimal, false),
+            attr("tests", Kind::NonNegativeInteger, false),
+            attr("failures", Kind::NonNegativeInteger, false),
+            attr("disabled", Kind::NonNegativeInteger, false),
+            attr("errors", Kind::NonNegativeInteger, false),
+        ],
+        children: &[
+            child("properties", 0, Some(1)),
+            child("testsuite", 0, None),
+            child("system-out", 0, Some(1)),
+            child("system-err", 0, Some(1)),
+        ],
+        text: false,
+    },
+    Element {
+        name: "testsuite",
+        attributes: &[
+            attr("name", Kind::String, true),
+            attr("tests", Kind::NonNegativeInteger, true),
+            attr("failures", Kind::NonNegativeInteger, false),
+            attr("errors", Kind::NonNegativeInteger, false),
+            attr("time", Kind::Decimal, false),
+            attr("disabled", Kind::NonNegativeInteger, false),
+            attr("skipped", Kind::NonNegativeInteger, false),
+            attr("assertions", Kind::NonNegativeInteger, false),
+            attr("timestamp", Kind::DateTime, false),
+            attr("hostname", Kind::String, false),
+            attr("file", Kind::String, false),
+            attr("id", Kind::NonNegativeInteger, false),
+            attr("package", Kind::String, false),
+        ],
+        children: &[
+            child("properties", 0, Some(1)),
+            child("testcase", 0, None),
+            child("system-out", 0, None),
+            child("system-err", 0, None),
+        ],
+        text: false,
+    },
+    Element {
+        name: "properties",
+        attributes: &[],
+        children: &[child("property", 1, None)],
+        text: false,
+    },
+    Element {
+        name: "property",
+        attributes: &[
+            attr("name", Kind::String, true),
+            attr("value", Kind::String, true),
+        ],
+        children: &[],
+        text: false,
+    },
+    Element {
+        name: "testcase",
+        attributes: &[
+            attr("name", Kind::String, true),
+            attr("assertions", Kind::NonNegativeInteger, false),
+            attr("time", Kind::Decimal, false),
+            attr("classname", Kind::String, false),
+            attr("file", Kind::String, false),
+            attr("line", Kind::NonNegativeInteger, false),
+            attr("hostname", Kind::String, false),
+            attr("group", Kind::String, false),
+            attr("timestamp", Kind::DateTime, false),
+            attr("status", Kind::String, false),
+        ],
+        children: &[
+            child("properties", 0, Some(1)),
+            child("skipped", 0, Some(1)),
+            child("error", 0, None),
+            child("failure", 0, None),
+            child("system-out", 0, None),
+            child("system-err", 0, None),
+        ],
+        text: false,
+    },
+    Element {
+        name: "error",
+        attributes: RESULT_ATTRIBUTES,
+        children: &[],
+        text: true,
+    },
+    Element {
+        name: "failure",
+        attributes: RESULT_ATTRIBUTES,
+        children: &[],
+        text: true,
+    },
+    Element {
+        name: "skipped",
+        attributes: &[
+            attr("type", Kind::String, false),
+            attr("message", Kind::String, false),
+        ],
+        children: &[],
+        text: true,
+    },
+    Element {
+        name: "system-out",
+        attributes: &[],
+        children: &[],
+        text: true,
+    },
+    Element {
+        name: "system-err",
+        attributes: &[],
+        children: &[],
+        text: true,
+    },
+];
+
+/// Validate a JUnit XML document against [`JUNIT_XSD`].
+///
+/// The root element may be either `<testsuites>` or `<testsuite>`.
+pub(crate) fn validate_xml(xml: &[u8]) -> Result<(), Vec<ValidationError>> {
+    let mut reader = Reader::from_reader(xml);
+    let mut errors = Vec::new();
+    let mut root = false;
+
+    let result = loop {
+        match reader.read_event() {
+            Ok(Event::Start(e)) | Ok(Event::Empty(e)) if root => {
+                errors.push(ValidationError::new(
+                    "/",
+                    format!("unexpected second root element `{}`", name(&e)),
+                ));
+                break Ok(());
+            }
+            Ok(Event::Start(e)) => {
+                root = true;
+                if let Err(e) = validate_root(&mut reader, &e, false, &mut errors) {
+                    break Err(e);
+                }
+            }
+            Ok(Event::Empty(e)) => {
+                root = true;
+                if let Err(e) = validate_root(&mut reader, &e, true, &mut errors) {
+                    break Err(e);
+                }
+            }
+            Ok(Event::Eof) => break Ok(()),
+            Ok(_) => {}
+            Err(e) => break Err(e),
+        }
+    };
+
+    if let Err(e) = result {
+        errors.push(ValidationError::new("/", e.to_string()));
+    } else if !root {
+        errors.push(ValidationError::new("/", "no root element found".into()));
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+/// Validate the root element whose start tag is `start`.
+fn validate_root(
+    reader: &mut Reader<&[u8]>,
+    start: &BytesStart,
+    empty: bool,
+    errors: &mut Vec<ValidationError>,
+) -> quick_xml::Result<()> {
+    let root = name(start);
+    let path = format!("/{}", root);
+    match element(&root).filter(|e| matches!(e.name, "testsuites" | "testsuite")) {
+        Some(element) => validate_element(reader, start, empty, element, &path, errors),
+        None => {
+            errors.push(ValidationError::new(
+                &path,
+                format!("unexpected root element `{}`", root),
+            ));
+            Ok(())
+        }
+    }
+}
+
+/// Validate the element whose start tag is `start` including all its children.
+fn validate_element(
+    reader: &mut Reader<&[u8]>,
+    start: &BytesStart,
+    empty: bool,
+    element: &Element,
+    path: &str,
+    errors: &mut Vec<ValidationError>,
+) -> quick_xml::Result<()> {
+    validate_attributes(start, element, path, errors)?;
+
+    let mut sequence = Sequence::new(element, path);
+    let mut indices: HashMap<String, usize> = HashMap::new();
+
+    if !empty {
+        loop {
+            match reader.read_event()? {
+                Event::Start(e) => {
+                    validate_child(reader, &e, false, &mut sequence, &mut indices, errors)?;
+                }
+                Event::Empty(e) => {
+                    validate_child(reader, &e, true, &mut sequence, &mut indices, errors)?;
+                }
+                Event::Text(e) if !element.text && !e.unescape()?.trim().is_empty() => {
+                    errors.push(ValidationError::new(path, "unexpected text content".into()));
+                }
+                Event::CData(_) if !element.text => {
+                    errors.push(ValidationError::new(
+                        path,
+                        "unexpected CDATA content".into(),
+                    ));
+                }
+                Event::End(_) => break,
+                Event::Eof => {
+                    errors.push(ValidationError::new(path, "unexpected end of file".into()));
+                    break;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    sequence.finish(errors);
+    Ok(())
+}
+
+/// Validate a child element against the content model of its parent.
+fn validate_child(
+    reader: &mut Reader<&[u8]>,
+    start: &BytesStart,
+    empty: bool,
+    sequence: &mut Sequence,
+    indices: &mut HashMap<String, usize>,
+    errors: &mut Vec<ValidationError>,
+) -> quick_xml::Result<()> {
+    let child = name(start);
+    let index = indices.entry(child.clone()).or_default();
+    let path = format!("{}/{}[{}]", sequence.path, child, index);
+    *index += 1;
+
+    match (sequence.accept(&child, errors), element(&child)) {
+        (true, Some(element)) => validate_element(reader, start, empty, element, &path, errors),
+        _ => {
+            if !empty {
+                reader.read_to_end(start.name())?;
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Check the attributes of `start` against the declaration of `element`.
+fn validate_attributes(
+    start: &BytesStart,
+    element: &Element,
+    path: &str,
+    errors: &mut Vec<ValidationError>,
+) -> quick_xml::Result<()> {
+    let mut present = Vec::new();
+    for attr in start.attributes() {
+        let attr = attr?;
+        let key = String::from_utf8_lossy(attr.key.as_ref()).into_owned();
+        let value = attr.unescape_value()?;
+        match element.attributes.iter().find(|a| a.name == key) {
+            Some(declaration) => {
+                if !is_valid(declaration.kind, &value) {
+                    errors.push(ValidationError::new(
+                        path,
+                        format!(
+                            "invalid value `{}` for attribute `{}`: expected {}",
+                            value,
+                            key,
+                            describe(declaration.kind)
+                        ),
+                    ));
+                }
+                present.push(declaration.name);
+            }
+            None => errors.push(ValidationError::new(
+                path,
+                format!("unexpected attribute `{}`", key),
+            )),
+        }
+    }
+    for declaration in element.attributes.iter().filter(|a| a.required) {
+        if !present.contains(&declaration.name) {
+            errors.push(ValidationError::new(
+                path,
+                format!("missing required attribute `{}`", declaration.name),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Tracks the position within the `<xs:sequence>` of an element.
+struct Sequence<'a> {
+    element: &'a Element,
+    path: &'a str,
+    position: usize,
+    count: usize,
+}
+
+impl<'a> Sequence<'a> {
+    fn new(element: &'a Element, path: &'a str) -> Self {
+        Sequence {
+            element,
+            path,
+            position: 0,
+            count: 0,
+        }
+    }
+
+    /// Advance the sequence by the child `name`, returns `false` if it is not allowed here.
+    fn accept(&mut self, name: &str, errors: &mut Vec<ValidationError>) -> bool {
+        let children = self.element.children;
+        match children[self.position.min(children.len())..]
+            .iter()
+            .position(|c| c.name == name)
+            .map(|i| i + self.position)
+        {
+            Some(i) if i == self.position => {
+                self.count += 1;
+                if children[i].max.is_some_and(|max| self.count > max) {
+                    errors.push(ValidationError::new(
+                        self.path,
+                        format!("too many `{}` elements", name),
+                    ));
+                }
+                true
+            }
+            Some(i) => {
+                self.check_missing(i, errors);
+                self.position = i;
+                self.count = 1;
+                true
+            }
+            None => {
+                let message = if children.iter().any(|c| c.name == name) {
+                    format!("element `{}` is out of order", name)
+                } else {
+                    format!(
+                        "element `{}` is not allowed in `{}`",
+                        name, self.element.name
+                    )
+                };
+                errors.push(ValidationError::new(self.path, message));
+                false
+            }
+        }
+    }
+
+    /// Check that all children up to the end of the sequence occurred often enough.
+    fn finish(self, errors: &mut Vec<ValidationError>) {
+        self.check_missing(self.element.children.len(), errors);
+    }
+
+    /// Report children before `end` that are missing.
+    fn check_missing(&self, end: usize, errors: &mut Vec<ValidationError>) {
+        let children = self.element.children;
+        for (i, c) in children.iter().enumerate().take(end).skip(self.position) {
+            let count = if i == self.position { self.count } else { 0 };
+            if count < c.min {
+                errors.push(ValidationError::new(
+                    self.path,
+                    format!("missing `{}` element", c.name),
+                ));
+            }
+        }
+    }
+}
+
+/// Look up the declaration of the element `name`.
+fn element(name: &str) -> Option<&'static Element> {
+    ELEMENTS.iter().find(|e| e.name == name)
+}
+
+/// The local name of the element `e`.
+fn name(e: &BytesStart) -> String {
+    String::from_utf8_lossy(e.name().as_ref()).into_owned()
+}
+
+/// Check if `value` is valid for the given [`Kind`].
+fn is_valid(kind: Kind, value: &str) -> bool {
+    match kind {
+        Kind::String => true,
+        Kind::NonNegativeInteger => value.parse::<u64>().is_ok(),
+        Kind::Decimal => {
+            let digits = value.strip_prefix(['+', '-']).unwrap_or(value);
+            let (integer, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+            !(integer.is_empty() && fraction.is_empty())
+                && integer.chars().all(|c| c.is_ascii_digit())
+                && fraction.chars().all(|c| c.is_ascii_digit())
+        }
+        Kind::DateTime => OffsetDateTime::parse(value, &Rfc3339).is_ok(),
+    }
+}
+
+/// Human readable description of a [`Kind`] for error messages.
+fn describe(kind: Kind) -> &'static str {
+    match kind {
+        Kind::String => "a string",
+        Kind::NonNegativeInteger => "a non-negative integer",
+        Kind::Decimal => "a decimal number",
+        Kind::DateTime => "a date time",
+    }
+}
@@ -9,7 +9,8 @@ use std::fs::{self, File};
 use std::process::Command;
 
 use junit_report::{
-    datetime, Duration, ReportBuilder, TestCase, TestCaseBuilder, TestSuiteBuilder,
+    datetime, Duration, ParseOptions, Report, ReportBuilder, ReportOptions, TestCase,
+    TestCaseBuilder, TestSuiteBuilder, JUNIT_XSD,
 };
 use once_cell::sync::Lazy;
 use regex::{Regex, RegexBuilder};
@@ -120,3 +121,328 @@ fn validate_generated_xml_schema() {
     eprint!("{}", String::from_utf8_lossy(&res.stderr));
     assert!(res.status.success());
 }
+
+#[test]
+fn write_split_to_dir() {
+    let dir = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("split");
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+
+    let timestamp = datetime!(2018-04-21 12:02 UTC);
+
+    let ts1 = TestSuiteBuilder::new("ts1")
+        .set_timestamp(timestamp)
+        .add_testcase(TestCase::success("test1", Duration::seconds(15)))
+        .build();
+    let ts2 = TestSuiteBuilder::new("a/b")
+        .set_timestamp(timestamp)
+        .add_testcase(TestCase::skipped("test2"))
+        .build();
+    let ts3 = TestSuiteBuilder::new("ts1")
+        .set_timestamp(timestamp)
+        .build();
+
+    let r = ReportBuilder::new().add_testsuites([ts1, ts2, ts3]).build();
+
+    let paths = r
+        .write_split_to_dir(&dir, |ts| format!("TEST-{}.xml", ts.name))
+        .unwrap();
+
+    let names: Vec<_> = paths
+        .iter()
+        .map(|p| p.file_name().unwrap().to_str().unwrap())
+        .collect();
+    assert_eq!(names, ["TEST-ts1.xml", "TEST-a_b.xml", "TEST-ts1-1.xml"]);
+    assert_eq!(fs::read_dir(&dir).unwrap().count(), 3);
+
+    for (path, ts) in paths.iter().zip(r.testsuites()) {
+        let parsed = Report::from_reader(File::open(path).unwrap()).unwrap();
+        assert_eq!(parsed.testsuites().len(), 1);
+        assert_eq!(parsed.testsuites()[0].name, ts.name);
+        assert_eq!(parsed.testsuites()[0].tests(), ts.tests());
+    }
+}
+
+#[test]
+fn write_to_file() {
+    let dir = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("to_file");
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("junit.xml");
+    fs::write(
+        &path,
+        "a much longer previous content that must be truncated",
+    )
+    .unwrap();
+
+    let r = ReportBuilder::new()
+        .add_testsuite(
+            TestSuiteBuilder::new("ts1")
+                .add_testcase(TestCase::success("good test", Duration::seconds(1)))
+                .build(),
+        )
+        .build();
+    r.write_to_file(&path).unwrap();
+
+    assert_eq!(
+        fs::read_to_string(&path).unwrap(),
+        r.to_xml_string().unwrap()
+    );
+    assert!(matches!(
+        r.write_to_file(dir.join("missing").join("junit.xml")),
+        Err(junit_report::Error::Io(_))
+    ));
+}
+
+#[test]
+fn write_atomic() {
+    let dir = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("atomic");
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("report.xml");
+    fs::write(&path, "partial").unwrap();
+
+    let r = ReportBuilder::new()
+        .add_testsuite(
+            TestSuiteBuilder::new("ts1")
+                .add_testcase(TestCase::success("good test", Duration::seconds(1)))
+                .build(),
+        )
+        .build();
+    r.write_atomic(&path).unwrap();
+
+    let parsed = Report::from_reader(File::open(&path).unwrap()).unwrap();
+    assert_eq!(parsed, r);
+    let files: Vec<_> = fs::read_dir(&dir)
+        .unwrap()
+        .map(|e| e.unwrap().file_name())
+        .collect();
+    assert_eq!(files, ["report.xml"]);
+
+    assert!(r
+        .write_atomic(dir.join("missing").join("report.xml"))
+        .is_err());
+    assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+}
+
+#[test]
+fn from_dir() {
+    let dir = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("surefire-reports");
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+
+    let timestamp = datetime!(2018-04-21 12:02 UTC);
+    let r = ReportBuilder::new()
+        .add_testsuite(
+            TestSuiteBuilder::new("ts1")
+                .set_timestamp(timestamp)
+                .add_testcase(TestCase::success("test1", Duration::seconds(15)))
+                .build(),
+        )
+        .add_testsuite(
+            TestSuiteBuilder::new("ts2")
+                .set_timestamp(timestamp)
+                .add_testcase(TestCase::skipped("test2"))
+                .build(),
+        )
+        .build();
+    r.write_split_to_dir(&dir, |ts| format!("TEST-{}.xml", ts.name))
+        .unwrap();
+    fs::write(dir.join("TEST-ts1.txt"), "summary").unwrap();
+
+    assert_eq!(Report::from_dir(&dir).unwrap(), r);
+
+    fs::write(dir.join("TEST-broken.xml"), "<testsuites>").unwrap();
+    let err = Report::from_dir(&dir).unwrap_err();
+    assert!(err.to_string().contains("TEST-broken.xml"));
+
+    let options = ParseOptions {
+        skip_invalid_files: true,
+        ..ParseOptions::default()
+    };
+    assert_eq!(Report::from_dir_with(&dir, &options).unwrap(), r);
+}
+
+#[test]
+fn validate_reference_xml_bundled_schema() {
+    let xsd = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("junit.xsd");
+    fs::write(&xsd, JUNIT_XSD).unwrap();
+
+    let res = Command::new("xmllint")
+        .arg("--schema")
+        .arg(&xsd)
+        .arg("tests/reference.xml")
+        .arg("--noout")
+        .output()
+        .expect("reference.xml does not validate against the bundled XML Schema");
+    print!("{}", String::from_utf8_lossy(&res.stdout));
+    eprint!("{}", String::from_utf8_lossy(&res.stderr));
+    assert!(res.status.success());
+}
+
+#[test]
+fn roundtrip_reference_xml() {
+    let report = Report::from_reader(File::open("tests/reference.xml").unwrap()).unwrap();
+    let mut out: Vec<u8> = Vec::new();
+    report.write_xml(&mut out).unwrap();
+    assert_eq!(Report::from_reader(out.as_slice()).unwrap(), report);
+}
+
+/// Element names and their attribute names in document order.
+fn structure(xml: &str) -> Vec<String> {
+    let tag = Regex::new(r#"<([a-z-]+)((?:\s+[a-z-]+="[^"]*")*)\s*/?>"#).unwrap();
+    let attribute = Regex::new(r#"([a-z-]+)=""#).unwrap();
+    tag.captures_iter(xml)
+        .map(|c| {
+            let attributes: Vec<_> = attribute
+                .captures_iter(&c[2])
+                .map(|a| a[1].to_string())
+                .collect();
+            format!("{}({})", &c[1], attributes.join(","))
+        })
+        .collect()
+}
+
+#[test]
+fn pytest_preset_matches_pytest_structure() {
+    let ts = TestSuiteBuilder::new("pytest")
+        .set_timestamp(datetime!(2024-03-01 10:00 UTC))
+        .add_property("python", "3.11")
+        .add_testcase(
+            TestCaseBuilder::success("test_ok", Duration::milliseconds(1))
+                .set_classname("tests.test_mod")
+                .set_filepath("tests/test_mod.py")
+                .build(),
+        )
+        .add_testcase(
+            TestCaseBuilder::failure(
+                "test_fail",
+                Duration::milliseconds(2),
+                "AssertionError",
+                "assert 1 == 2",
+            )
+            .set_classname("tests.test_mod")
+            .set_trace("tests/test_mod.py:6: AssertionError")
+            .build(),
+        )
+        .add_testcase(
+            TestCaseBuilder::error(
+                "test_error",
+                Duration::milliseconds(3),
+                "RuntimeError",
+                "failed on setup",
+            )
+            .set_classname("tests.test_mod")
+            .set_trace("RuntimeError: boom")
+            .build(),
+        )
+        .add_testcase(TestCase::skipped("test_skip").with_classname("tests.test_mod"))
+        .build();
+    let r = ReportBuilder::new().add_testsuite(ts).build();
+
+    let mut out: Vec<u8> = Vec::new();
+    r.write_xml_with(&mut out, &ReportOptions::pytest())
+        .unwrap();
+    let report = String::from_utf8(out).unwrap();
+
+    let reference = fs::read_to_string("tests/pytest.xml").unwrap();
+
+    assert_eq!(structure(&report), structure(&reference));
+    assert!(
+        report.contains(r#"<testcase classname="tests.test_mod" name="test_ok" time="0.001"/>"#)
+    );
+}
+
+#[test]
+fn validate_ant_preset_xml_schema() {
+    let ts = TestSuiteBuilder::new("FooTest")
+        .set_timestamp(datetime!(2018-04-21 12:02 UTC))
+        .set_package("com.example")
+        .add_property("java.version", "21")
+        .add_testcase(
+            TestCaseBuilder::success("testOk", Duration::seconds(15))
+                .set_classname("com.example.FooTest")
+                .build(),
+        )
+        .add_testcase(TestCase::error(
+            "testError",
+            Duration::seconds(5),
+            "java.io.IOException",
+            "Could not clone",
+        ))
+        .add_testcase(TestCase::failure_with_body(
+            "testFail",
+            Duration::seconds(10),
+            "java.lang.AssertionError",
+            "expected 1",
+            "at com.example.FooTest.testFail(FooTest.java:12)",
+        ))
+        .build();
+    let r = ReportBuilder::new().add_testsuite(ts).build();
+
+    let path = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("ant.xml");
+    let mut f = File::create(&path).unwrap();
+    r.write_xml_with(&mut f, &ReportOptions::ant()).unwrap();
+
+    let res = Command::new("xmllint")
+        .arg("--schema")
+        .arg("tests/JUnit-alternative.xsd")
+        .arg(&path)
+        .arg("--noout")
+        .output()
+        .expect("ant.xml does not validate against XML Schema");
+    print!("{}", String::from_utf8_lossy(&res.stdout));
+    eprint!("{}", String::from_utf8_lossy(&res.stderr));
+    assert!(res.status.success());
+}
+
+#[test]
+#[ignore = "slow, run with `cargo test --release -- --ignored`"]
+fn write_huge_testsuite() {
+    const CASES: usize = 1_000_000;
+
+    let mut ts = TestSuiteBuilder::new("huge")
+        .set_timestamp(datetime!(2018-04-21 12:02 UTC))
+        .build();
+    ts.add_testcases((0..CASES).map(|i| {
+        if i % 1000 == 0 {
+            TestCase::failure(
+                &format!("case {}", i),
+                Duration::milliseconds(1),
+                "assert",
+                "nope",
+            )
+        } else {
+            TestCase::success(&format!("case {}", i), Duration::milliseconds(1))
+        }
+    }));
+    let r = ReportBuilder::new().add_testsuite(ts).build();
+
+    // Writing iterates over the test cases, so a small stack is sufficient
+    let counter = std::thread::Builder::new()
+        .stack_size(256 * 1024)
+        .spawn(move || {
+            let mut counter = CountingSink(0);
+            r.write_xml(&mut counter).unwrap();
+            counter
+        })
+        .unwrap()
+        .join()
+        .unwrap();
+
+    assert!(counter.0 > CASES * "<testcase name=\"case 0\" time=\"0.001\"/>".len());
+}
+
+/// Sink that only counts the bytes written to it.
+struct CountingSink(usize);
+
+impl std::io::Write for CountingSink {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0 += buf.len();
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}